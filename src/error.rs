@@ -0,0 +1,162 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Errors returned by loaders and constructors of the crate.
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read.
+    MissingFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A file could not be written.
+    FileWrite {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// An image could not be decoded.
+    ImageDecode(image::ImageError),
+    /// An image could not be encoded or written.
+    ImageEncode(image::ImageError),
+    /// An OBJ file could not be parsed.
+    ObjParse(tobj::LoadError),
+    /// An MTL file could not be parsed.
+    MtlParse(tobj::LoadError),
+    /// A font could not be parsed.
+    FontParse(&'static str),
+    /// No adapter compatible with the requested options was found.
+    NoAdapter,
+    /// The surface reports no formats for the adapter.
+    UnsupportedSurface,
+    /// The window surface can't be read back, the Bundle is not created with a readable surface.
+    UnreadableSurface,
+    /// The device ran out of memory while acquiring a surface texture.
+    SurfaceOutOfMemory,
+    /// The adapter does not support required features.
+    MissingFeatures(wgpu::Features),
+    /// The adapter failed to create a device.
+    RequestDevice(wgpu::RequestDeviceError),
+    /// MSAA with the sample count is not supported.
+    UnsupportedSampleCount(u32),
+    /// A texture format is not supported by the operation.
+    UnsupportedFormat(wgpu::TextureFormat),
+    /// Layers of a texture differ in size, or a cube face is not square.
+    ImageSizeMismatch {
+        expected: (u32, u32),
+        found: (u32, u32),
+    },
+    /// A texture array needs at least one image.
+    EmptyTextureArray,
+    /// An image or texture has no texels.
+    InvalidDimensions(&'static str),
+    /// A KTX2 container could not be parsed.
+    Ktx2Parse(ktx2::ParseError),
+    /// A DDS container could not be parsed.
+    DdsParse(ddsfile::Error),
+    /// A texture container is malformed or uses a feature which is not supported.
+    UnsupportedContainer(&'static str),
+    /// A buffer could not be mapped for reading.
+    BufferMap(wgpu::BufferAsyncError),
+    /// The OS failed to create a window.
+    WindowCreation(winit::error::OsError),
+    /// An image could not be used as a window icon.
+    BadIcon(winit::window::BadIcon),
+    /// Input bindings could not be parsed.
+    BindingsParse(serde_json::Error),
+    /// An input recording could not be parsed.
+    RecordingParse(serde_json::Error),
+    /// The gamepad backend could not be initialized.
+    #[cfg(feature = "gilrs")]
+    GamepadInit(String),
+}
+
+/// Result type of the crate.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn missing_file(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::MissingFile {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingFile { path, source } => {
+                write!(f, "failed to read file {}: {}", path.display(), source)
+            }
+            Self::FileWrite { path, source } => {
+                write!(f, "failed to write file {}: {}", path.display(), source)
+            }
+            Self::ImageDecode(error) => write!(f, "failed to decode image: {}", error),
+            Self::ImageEncode(error) => write!(f, "failed to encode image: {}", error),
+            Self::ObjParse(error) => write!(f, "failed to parse OBJ file: {}", error),
+            Self::MtlParse(error) => write!(f, "failed to parse MTL file: {}", error),
+            Self::FontParse(error) => write!(f, "failed to parse font: {}", error),
+            Self::NoAdapter => write!(f, "no compatible adapter found"),
+            Self::UnsupportedSurface => write!(f, "surface supports no formats of the adapter"),
+            Self::UnreadableSurface => write!(f, "surface is not readable, enable BundleDescriptor::readable_surface"),
+            Self::SurfaceOutOfMemory => write!(f, "out of memory while acquiring a surface texture"),
+            Self::MissingFeatures(features) => write!(f, "adapter does not support features {:?}", features),
+            Self::RequestDevice(error) => write!(f, "failed to request device: {}", error),
+            Self::UnsupportedSampleCount(count) => write!(f, "unsupported sample count {}", count),
+            Self::UnsupportedFormat(format) => write!(f, "unsupported texture format {:?}", format),
+            Self::ImageSizeMismatch { expected, found } => write!(
+                f,
+                "image is {}x{}, expected {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            Self::EmptyTextureArray => write!(f, "texture array has no images"),
+            Self::InvalidDimensions(reason) => write!(f, "invalid dimensions: {}", reason),
+            Self::Ktx2Parse(error) => write!(f, "failed to parse KTX2 container: {}", error),
+            Self::DdsParse(error) => write!(f, "failed to parse DDS container: {}", error),
+            Self::UnsupportedContainer(reason) => write!(f, "unsupported texture container: {}", reason),
+            Self::BufferMap(error) => write!(f, "failed to map buffer: {}", error),
+            Self::WindowCreation(error) => write!(f, "failed to create window: {}", error),
+            Self::BadIcon(error) => write!(f, "invalid window icon: {}", error),
+            Self::BindingsParse(error) => write!(f, "failed to parse bindings: {}", error),
+            Self::RecordingParse(error) => write!(f, "failed to parse input recording: {}", error),
+            #[cfg(feature = "gilrs")]
+            Self::GamepadInit(error) => write!(f, "failed to initialize gamepads: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MissingFile { source, .. } | Self::FileWrite { source, .. } => Some(source),
+            Self::ImageDecode(error) | Self::ImageEncode(error) => Some(error),
+            Self::ObjParse(error) | Self::MtlParse(error) => Some(error),
+            Self::RequestDevice(error) => Some(error),
+            Self::Ktx2Parse(error) => Some(error),
+            Self::DdsParse(error) => Some(error),
+            Self::BufferMap(error) => Some(error),
+            Self::WindowCreation(error) => Some(error),
+            Self::BadIcon(error) => Some(error),
+            Self::BindingsParse(error) | Self::RecordingParse(error) => Some(error),
+            Self::FontParse(_) | Self::NoAdapter | Self::MissingFeatures(_) | Self::UnsupportedFormat(_) => None,
+            Self::UnsupportedSurface | Self::UnreadableSurface | Self::SurfaceOutOfMemory => None,
+            Self::UnsupportedSampleCount(_) => None,
+            Self::ImageSizeMismatch { .. } | Self::EmptyTextureArray | Self::UnsupportedContainer(_) => None,
+            Self::InvalidDimensions(_) => None,
+            #[cfg(feature = "gilrs")]
+            Self::GamepadInit(_) => None,
+        }
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for Error {
+    fn from(error: wgpu::RequestDeviceError) -> Self {
+        Self::RequestDevice(error)
+    }
+}
+
+impl From<wgpu::BufferAsyncError> for Error {
+    fn from(error: wgpu::BufferAsyncError) -> Self {
+        Self::BufferMap(error)
+    }
+}
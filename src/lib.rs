@@ -1,2 +1,5 @@
+pub mod error;
+pub use error::*;
+
 pub mod main_loop;
-pub mod rendering;
\ No newline at end of file
+pub mod rendering;
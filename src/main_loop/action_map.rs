@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{GamepadAxis, GamepadButton, Input, Key, MouseButton};
+use crate::{Error, Result};
+
+/// An input which can trigger an action.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    /// A keyboard key.
+    Key(Key),
+    /// A mouse button, see Input::mouse_pressed.
+    MouseButton(MouseButton),
+    /// Keys which have to be held together, such as LControl and S.
+    Chord(Vec<Key>),
+    /// A button of any connected gamepad.
+    GamepadButton(GamepadButton),
+    /// One direction of an analog axis of any connected gamepad.
+    /// It is held when the axis is pushed past half of its range.
+    GamepadAxis { axis: GamepadAxis, positive: bool },
+}
+
+impl Binding {
+    /// Returns true if the binding was pressed.
+    /// A chord is pressed when its last key is pressed while the others are held.
+    pub fn pressed(&self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.key_pressed(*key),
+            Self::MouseButton(button) => input.mouse_pressed(*button),
+            Self::Chord(keys) => {
+                !keys.is_empty()
+                    && keys.iter().all(|key| input.key_held(*key))
+                    && keys.iter().any(|key| input.key_pressed(*key))
+            }
+            Self::GamepadButton(button) => {
+                let gamepads = input.gamepads();
+                gamepads.ids().any(|id| gamepads.button_pressed(id, *button))
+            }
+            Self::GamepadAxis { axis, positive } => {
+                let gamepads = input.gamepads();
+                gamepads.ids().any(|id| {
+                    let previous = directed(gamepads.previous_axis(id, *axis), *positive);
+                    let current = directed(gamepads.axis(id, *axis), *positive);
+                    previous < Self::AXIS_THRESHOLD && current >= Self::AXIS_THRESHOLD
+                })
+            }
+        }
+    }
+
+    /// Returns true if the binding is held.
+    pub fn held(&self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.key_held(*key),
+            Self::MouseButton(button) => input.mouse_held(*button),
+            Self::Chord(keys) => !keys.is_empty() && keys.iter().all(|key| input.key_held(*key)),
+            Self::GamepadButton(button) => {
+                let gamepads = input.gamepads();
+                gamepads.ids().any(|id| gamepads.button_held(id, *button))
+            }
+            Self::GamepadAxis { .. } => self.value(input) >= Self::AXIS_THRESHOLD,
+        }
+    }
+
+    /// Returns true if the binding was released.
+    /// A chord is released when one of its keys is released while the others are held.
+    pub fn released(&self, input: &Input) -> bool {
+        match self {
+            Self::Key(key) => input.key_released(*key),
+            Self::MouseButton(button) => input.mouse_released(*button),
+            Self::Chord(keys) => keys.iter().any(|key| {
+                input.key_released(*key)
+                    && keys.iter().filter(|other| *other != key).all(|other| input.key_held(*other))
+            }),
+            Self::GamepadButton(button) => {
+                let gamepads = input.gamepads();
+                gamepads.ids().any(|id| gamepads.button_released(id, *button))
+            }
+            Self::GamepadAxis { axis, positive } => {
+                let gamepads = input.gamepads();
+                gamepads.ids().any(|id| {
+                    let previous = directed(gamepads.previous_axis(id, *axis), *positive);
+                    let current = directed(gamepads.axis(id, *axis), *positive);
+                    previous >= Self::AXIS_THRESHOLD && current < Self::AXIS_THRESHOLD
+                })
+            }
+        }
+    }
+
+    /// Portion of an axis range at which a GamepadAxis binding counts as held.
+    const AXIS_THRESHOLD: f32 = 0.5;
+
+    /// Returns a value in 0..1, analog for gamepad axes.
+    fn value(&self, input: &Input) -> f32 {
+        match self {
+            Self::GamepadAxis { axis, positive } => {
+                let gamepads = input.gamepads();
+                gamepads
+                    .ids()
+                    .map(|id| directed(gamepads.axis(id, *axis), *positive))
+                    .fold(0.0, f32::max)
+            }
+            _ if self.held(input) => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Returns a value of an axis in one direction, clamped to 0..1.
+fn directed(value: f32, positive: bool) -> f32 {
+    if positive {
+        value.max(0.0)
+    } else {
+        (-value).max(0.0)
+    }
+}
+
+/// Two bindings producing a value in -1..1.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AxisBinding {
+    pub negative: Binding,
+    pub positive: Binding,
+}
+
+impl AxisBinding {
+    /// Creates a new AxisBinding.
+    pub fn new(negative: Binding, positive: Binding) -> Self {
+        Self { negative, positive }
+    }
+
+    fn value(&self, input: &Input) -> f32 {
+        self.positive.value(input) - self.negative.value(input)
+    }
+}
+
+/// Four bindings producing a vector with x and y in -1..1.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Axis2dBinding {
+    pub left: Binding,
+    pub right: Binding,
+    pub down: Binding,
+    pub up: Binding,
+}
+
+impl Axis2dBinding {
+    /// Creates a new Axis2dBinding.
+    pub fn new(left: Binding, right: Binding, down: Binding, up: Binding) -> Self {
+        Self {
+            left,
+            right,
+            down,
+            up,
+        }
+    }
+
+    /// Creates a new Axis2dBinding of W, A, S and D keys.
+    pub fn wasd() -> Self {
+        Self::new(
+            Binding::Key(Key::A),
+            Binding::Key(Key::D),
+            Binding::Key(Key::S),
+            Binding::Key(Key::W),
+        )
+    }
+
+    /// Creates a new Axis2dBinding of the left stick of gamepads.
+    pub fn left_stick() -> Self {
+        Self::gamepad_stick(GamepadAxis::LeftStickX, GamepadAxis::LeftStickY)
+    }
+
+    /// Creates a new Axis2dBinding of the right stick of gamepads.
+    pub fn right_stick() -> Self {
+        Self::gamepad_stick(GamepadAxis::RightStickX, GamepadAxis::RightStickY)
+    }
+
+    fn gamepad_stick(x: GamepadAxis, y: GamepadAxis) -> Self {
+        let binding = |axis, positive| Binding::GamepadAxis { axis, positive };
+        Self::new(binding(x, false), binding(x, true), binding(y, false), binding(y, true))
+    }
+
+    /// Creates a new Axis2dBinding of arrow keys.
+    pub fn arrows() -> Self {
+        Self::new(
+            Binding::Key(Key::Left),
+            Binding::Key(Key::Right),
+            Binding::Key(Key::Down),
+            Binding::Key(Key::Up),
+        )
+    }
+
+    fn value(&self, input: &Input) -> glam::Vec2 {
+        glam::vec2(
+            self.right.value(input) - self.left.value(input),
+            self.up.value(input) - self.down.value(input),
+        )
+    }
+}
+
+/// Maps named actions and axes to input bindings, so controls can be rebound without code changes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionMap {
+    #[serde(default)]
+    actions: BTreeMap<String, Vec<Binding>>,
+    #[serde(default)]
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+    #[serde(default)]
+    axes_2d: BTreeMap<String, Vec<Axis2dBinding>>,
+}
+
+impl ActionMap {
+    /// Creates a new empty ActionMap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding to an action.
+    pub fn bind(&mut self, action: &str, binding: Binding) {
+        self.actions.entry(action.to_string()).or_default().push(binding);
+    }
+
+    /// Adds a binding to an axis.
+    pub fn bind_axis(&mut self, axis: &str, binding: AxisBinding) {
+        self.axes.entry(axis.to_string()).or_default().push(binding);
+    }
+
+    /// Adds a binding to a 2D axis.
+    pub fn bind_axis_2d(&mut self, axis: &str, binding: Axis2dBinding) {
+        self.axes_2d.entry(axis.to_string()).or_default().push(binding);
+    }
+
+    /// Removes all bindings of an action or an axis.
+    pub fn unbind(&mut self, name: &str) {
+        self.actions.remove(name);
+        self.axes.remove(name);
+        self.axes_2d.remove(name);
+    }
+
+    /// Returns bindings of an action.
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.actions.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns bindings of an axis.
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns bindings of a 2D axis.
+    pub fn axis_2d_bindings(&self, axis: &str) -> &[Axis2dBinding] {
+        self.axes_2d.get(axis).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns true if any binding of the action was pressed.
+    pub fn action_pressed(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|binding| binding.pressed(input))
+    }
+
+    /// Returns true if any binding of the action is held.
+    pub fn action_held(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|binding| binding.held(input))
+    }
+
+    /// Returns true if any binding of the action was released.
+    pub fn action_released(&self, input: &Input, action: &str) -> bool {
+        self.bindings(action).iter().any(|binding| binding.released(input))
+    }
+
+    /// Returns a value of the axis in -1..1.
+    pub fn axis(&self, input: &Input, axis: &str) -> f32 {
+        self.axis_bindings(axis)
+            .iter()
+            .map(|binding| binding.value(input))
+            .sum::<f32>()
+            .clamp(-1.0, 1.0)
+    }
+
+    /// Returns a value of the 2D axis, its length is at most 1.
+    pub fn axis_2d(&self, input: &Input, axis: &str) -> glam::Vec2 {
+        self.axis_2d_bindings(axis)
+            .iter()
+            .fold(glam::Vec2::ZERO, |sum, binding| sum + binding.value(input))
+            .clamp_length_max(1.0)
+    }
+
+    /// Parses bindings from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(Error::BindingsParse)
+    }
+
+    /// Serializes bindings into a JSON string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("bindings are always serializable")
+    }
+
+    /// Loads bindings from a JSON file.
+    pub fn load(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path).map_err(|e| Error::missing_file(path, e))?;
+        Self::from_json(&json)
+    }
+
+    /// Saves bindings into a JSON file.
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.to_json()).map_err(|e| Error::FileWrite {
+            path: path.into(),
+            source: e,
+        })
+    }
+}
@@ -0,0 +1,209 @@
+use winit::dpi::PhysicalSize;
+use winit::event_loop::EventLoop;
+use winit::window::WindowBuilder;
+
+use super::{FramePacing, Input, MainLoop};
+use crate::{Error, Result};
+
+/// Fullscreen mode of a window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fullscreen {
+    /// Borderless window covering the whole current monitor.
+    Borderless,
+    /// Exclusive fullscreen using the best video mode of the primary monitor.
+    Exclusive,
+}
+
+/// Builder for a MainLoop and its window.
+pub struct MainLoopBuilder {
+    title: String,
+    size: (u32, u32),
+    min_size: Option<(u32, u32)>,
+    max_size: Option<(u32, u32)>,
+    resizable: bool,
+    fullscreen: Option<Fullscreen>,
+    decorations: bool,
+    maximized: bool,
+    transparent: bool,
+    icon: Option<image::RgbaImage>,
+    icon_path: Option<String>,
+    vsync: bool,
+    frame_pacing: FramePacing,
+}
+
+impl MainLoopBuilder {
+    /// Creates a new builder with an 800x600 resizable window.
+    pub fn new(window_title: &str) -> Self {
+        Self {
+            title: window_title.to_string(),
+            size: (800, 600),
+            min_size: None,
+            max_size: None,
+            resizable: true,
+            fullscreen: None,
+            decorations: true,
+            maximized: false,
+            transparent: false,
+            icon: None,
+            icon_path: None,
+            vsync: true,
+            frame_pacing: FramePacing::default(),
+        }
+    }
+
+    /// Sets the initial inner size of the window in physical pixels.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    /// Sets the minimal inner size of the window in physical pixels.
+    pub fn with_min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    /// Sets the maximal inner size of the window in physical pixels.
+    pub fn with_max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// Sets whether the window can be resized by the user.
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Sets the fullscreen mode, None for a windowed window.
+    pub fn with_fullscreen(mut self, fullscreen: Option<Fullscreen>) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Sets whether the window has a title bar and borders.
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Sets whether the window starts maximized.
+    pub fn with_maximized(mut self, maximized: bool) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    /// Sets whether the window background can be transparent.
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Sets the window icon.
+    pub fn with_icon(mut self, icon: image::RgbaImage) -> Self {
+        self.icon = Some(icon);
+        self.icon_path = None;
+        self
+    }
+
+    /// Sets the window icon loaded from an image file.
+    pub fn with_icon_path(mut self, path: &str) -> Self {
+        self.icon_path = Some(path.to_string());
+        self.icon = None;
+        self
+    }
+
+    /// Sets the vsync preference.
+    /// Bundles created by Bundle::for_main_loop and Windows::bundle use it.
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    /// Sets how often the MainLoop runs frames.
+    pub fn with_frame_pacing(mut self, frame_pacing: FramePacing) -> Self {
+        self.frame_pacing = frame_pacing;
+        self
+    }
+
+    /// Creates the window and the MainLoop.
+    pub fn build(self) -> Result<MainLoop> {
+        let event_loop = EventLoop::new();
+
+        let icon = match (self.icon, self.icon_path) {
+            (Some(icon), _) => Some(icon),
+            (None, Some(path)) => {
+                let bytes = std::fs::read(&path).map_err(|e| Error::missing_file(path, e))?;
+                let icon = image::load_from_memory(&bytes).map_err(Error::ImageDecode)?;
+                Some(icon.to_rgba8())
+            }
+            (None, None) => None,
+        };
+        let icon = match icon {
+            Some(icon) => {
+                let (width, height) = icon.dimensions();
+                Some(
+                    winit::window::Icon::from_rgba(icon.into_raw(), width, height)
+                        .map_err(Error::BadIcon)?,
+                )
+            }
+            None => None,
+        };
+
+        let fullscreen = match self.fullscreen {
+            Some(Fullscreen::Borderless) => Some(winit::window::Fullscreen::Borderless(None)),
+            Some(Fullscreen::Exclusive) => event_loop
+                .primary_monitor()
+                .and_then(|monitor| {
+                    monitor.video_modes().max_by_key(|mode| {
+                        let size = mode.size();
+                        (size.width * size.height, mode.refresh_rate(), mode.bit_depth())
+                    })
+                })
+                .map(winit::window::Fullscreen::Exclusive),
+            None => None,
+        };
+
+        let mut window_builder = WindowBuilder::new()
+            .with_title(self.title)
+            .with_inner_size(PhysicalSize::new(self.size.0, self.size.1))
+            .with_resizable(self.resizable)
+            .with_fullscreen(fullscreen)
+            .with_decorations(self.decorations)
+            .with_maximized(self.maximized)
+            .with_transparent(self.transparent)
+            .with_window_icon(icon);
+
+        if let Some((width, height)) = self.min_size {
+            window_builder = window_builder.with_min_inner_size(PhysicalSize::new(width, height));
+        }
+        if let Some((width, height)) = self.max_size {
+            window_builder = window_builder.with_max_inner_size(PhysicalSize::new(width, height));
+        }
+
+        let window = window_builder
+            .build(&event_loop)
+            .map_err(Error::WindowCreation)?;
+
+        #[allow(unused_mut)]
+        let mut input = Input::new();
+        #[cfg(feature = "gilrs")]
+        match super::GilrsBackend::new() {
+            Ok(backend) => input.set_gamepad_backend(Box::new(backend)),
+            Err(error) => log::warn!("{}", error),
+        }
+
+        let primary = window.id();
+        Ok(MainLoop {
+            event_loop,
+            windows: std::iter::once((primary, std::sync::Arc::new(window))).collect(),
+            primary,
+            input,
+            fixed_timestep: None,
+            frame_pacing: self.frame_pacing,
+            vsync: self.vsync,
+            input_recording: None,
+            input_replay: None,
+        })
+    }
+}
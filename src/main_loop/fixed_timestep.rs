@@ -0,0 +1,70 @@
+/// Accumulator for running updates with a fixed time step.
+pub struct FixedTimestep {
+    step: f32,
+    max_steps: u32,
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    /// The default maximum of steps run in one frame.
+    pub const DEFAULT_MAX_STEPS: u32 = 5;
+
+    /// Creates a new FixedTimestep running tick_rate steps per second.
+    pub fn new(tick_rate: f32) -> Self {
+        Self {
+            step: 1.0 / tick_rate,
+            max_steps: Self::DEFAULT_MAX_STEPS,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Returns its tick rate (steps per second).
+    pub fn tick_rate(&self) -> f32 {
+        1.0 / self.step
+    }
+
+    /// Sets its tick rate (steps per second).
+    pub fn set_tick_rate(&mut self, tick_rate: f32) {
+        self.step = 1.0 / tick_rate;
+    }
+
+    /// Returns the duration of one step in seconds.
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Returns the maximum of steps run in one frame.
+    pub fn max_steps(&self) -> u32 {
+        self.max_steps
+    }
+
+    /// Sets the maximum of steps run in one frame.
+    /// Time which would need more steps is dropped, so a slow frame can't cause even slower ones.
+    pub fn set_max_steps(&mut self, max_steps: u32) {
+        self.max_steps = max_steps;
+    }
+
+    /// Adds a frame time and returns how many steps should be run.
+    pub fn advance(&mut self, delta: f32) -> u32 {
+        self.accumulator += delta;
+
+        let mut steps = 0;
+        while self.accumulator >= self.step {
+            if steps == self.max_steps {
+                self.accumulator %= self.step;
+                break;
+            }
+
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        steps
+    }
+
+    /// Returns how far the time is between the last and the next step (0..1).
+    /// Should be used to interpolate rendered states.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+}
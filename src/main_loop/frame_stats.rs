@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Timing statistics of recent frames, all times are in seconds.
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    frame_times: VecDeque<f32>,
+    capacity: usize,
+    frame_count: u64,
+    update_time: f32,
+    render_time: f32,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl FrameStats {
+    /// The default number of frames the statistics are computed from.
+    pub const DEFAULT_CAPACITY: usize = 120;
+
+    /// Creates new FrameStats computed from the last capacity frames.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(capacity.max(1)),
+            capacity: capacity.max(1),
+            frame_count: 0,
+            update_time: 0.0,
+            render_time: 0.0,
+        }
+    }
+
+    /// Adds a frame time.
+    pub fn add_frame(&mut self, frame_time: f32) {
+        if self.frame_times.len() == self.capacity {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+        self.frame_count += 1;
+    }
+
+    /// Sets the time spent in the last update.
+    pub fn set_update_time(&mut self, update_time: f32) {
+        self.update_time = update_time;
+    }
+
+    /// Sets the time spent in the last render.
+    pub fn set_render_time(&mut self, render_time: f32) {
+        self.render_time = render_time;
+    }
+
+    /// Returns the time of the last frame, the delta of the current update.
+    pub fn delta(&self) -> f32 {
+        self.frame_times.back().copied().unwrap_or(0.0)
+    }
+
+    /// Returns the number of all frames added.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Returns the average frame time.
+    pub fn average(&self) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+
+        self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32
+    }
+
+    /// Returns the average number of frames per second.
+    pub fn fps(&self) -> f32 {
+        let average = self.average();
+        if average > 0.0 {
+            1.0 / average
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the shortest frame time.
+    pub fn min(&self) -> f32 {
+        self.frame_times.iter().copied().reduce(f32::min).unwrap_or(0.0)
+    }
+
+    /// Returns the longest frame time.
+    pub fn max(&self) -> f32 {
+        self.frame_times.iter().copied().reduce(f32::max).unwrap_or(0.0)
+    }
+
+    /// Returns the frame time which percent of frames do not exceed (0..100).
+    pub fn percentile(&self, percent: f32) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+
+        let mut sorted = self.frame_times.iter().copied().collect::<Vec<_>>();
+        sorted.sort_by(f32::total_cmp);
+
+        let rank = (percent.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+        sorted[rank.saturating_sub(1)]
+    }
+
+    /// Returns the time spent in the last update, including fixed updates.
+    pub fn update_time(&self) -> f32 {
+        self.update_time
+    }
+
+    /// Returns the time spent in the last render.
+    pub fn render_time(&self) -> f32 {
+        self.render_time
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "FPS {:.1}", self.fps())?;
+        writeln!(
+            f,
+            "frame {:.2} ms (avg {:.2}, min {:.2}, max {:.2}, p99 {:.2})",
+            self.delta() * 1000.0,
+            self.average() * 1000.0,
+            self.min() * 1000.0,
+            self.max() * 1000.0,
+            self.percentile(99.0) * 1000.0,
+        )?;
+        write!(
+            f,
+            "update {:.2} ms, render {:.2} ms",
+            self.update_time * 1000.0,
+            self.render_time * 1000.0,
+        )
+    }
+}
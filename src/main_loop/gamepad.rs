@@ -0,0 +1,410 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+/// Identifies a gamepad while it is connected.
+pub type GamepadId = usize;
+
+/// A digital gamepad button, named after the layout of an Xbox controller.
+/// Triggers are analog, see GamepadAxis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GamepadButton {
+    /// The bottom face button (A on Xbox, Cross on PlayStation).
+    South,
+    /// The right face button (B on Xbox, Circle on PlayStation).
+    East,
+    /// The top face button (Y on Xbox, Triangle on PlayStation).
+    North,
+    /// The left face button (X on Xbox, Square on PlayStation).
+    West,
+    LeftBumper,
+    RightBumper,
+    /// Pressing the left stick.
+    LeftStick,
+    /// Pressing the right stick.
+    RightStick,
+    Select,
+    Start,
+    /// The logo button in the middle of the gamepad.
+    Mode,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// An analog gamepad input.
+/// Sticks are in -1..1 with positive x to the right and positive y up, triggers are in 0..1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// A change of a gamepad reported by a GamepadBackend.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GamepadEvent {
+    Connected { id: GamepadId, name: String },
+    Disconnected(GamepadId),
+    ButtonPressed(GamepadId, GamepadButton),
+    ButtonReleased(GamepadId, GamepadButton),
+    /// A new raw value of an axis, without the dead zone applied.
+    AxisChanged(GamepadId, GamepadAxis, f32),
+}
+
+/// Source of gamepad events polled by Input once per frame.
+pub trait GamepadBackend {
+    /// Appends events which happened since the last poll.
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>);
+}
+
+/// GamepadBackend driven by code instead of hardware, meant for tests.
+/// Clones share their events, so a clone can be given to Input while the original pushes events.
+#[derive(Clone, Debug, Default)]
+pub struct MockGamepadBackend {
+    events: Rc<RefCell<Vec<GamepadEvent>>>,
+}
+
+impl MockGamepadBackend {
+    /// Creates a new MockGamepadBackend without any gamepads.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues an event for the next poll.
+    pub fn push(&self, event: GamepadEvent) {
+        self.events.borrow_mut().push(event);
+    }
+
+    /// Queues a connection of a gamepad.
+    pub fn connect(&self, id: GamepadId, name: &str) {
+        self.push(GamepadEvent::Connected {
+            id,
+            name: name.to_string(),
+        });
+    }
+
+    /// Queues a disconnection of a gamepad.
+    pub fn disconnect(&self, id: GamepadId) {
+        self.push(GamepadEvent::Disconnected(id));
+    }
+
+    /// Queues a button press.
+    pub fn press(&self, id: GamepadId, button: GamepadButton) {
+        self.push(GamepadEvent::ButtonPressed(id, button));
+    }
+
+    /// Queues a button release.
+    pub fn release(&self, id: GamepadId, button: GamepadButton) {
+        self.push(GamepadEvent::ButtonReleased(id, button));
+    }
+
+    /// Queues a new value of an axis.
+    pub fn set_axis(&self, id: GamepadId, axis: GamepadAxis, value: f32) {
+        self.push(GamepadEvent::AxisChanged(id, axis, value));
+    }
+}
+
+impl GamepadBackend for MockGamepadBackend {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        events.append(&mut self.events.borrow_mut());
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct GamepadState {
+    name: String,
+    buttons: BTreeSet<GamepadButton>,
+    previous_buttons: BTreeSet<GamepadButton>,
+    axes: BTreeMap<GamepadAxis, f32>,
+    previous_axes: BTreeMap<GamepadAxis, f32>,
+}
+
+/// State of connected gamepads, updated once per frame.
+#[derive(Clone, Debug)]
+pub struct Gamepads {
+    gamepads: BTreeMap<GamepadId, GamepadState>,
+    events: Vec<GamepadEvent>,
+    dead_zone: f32,
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gamepads {
+    /// Dead zone used by new Gamepads.
+    pub const DEFAULT_DEAD_ZONE: f32 = 0.15;
+
+    /// Creates a new struct without any gamepads.
+    pub fn new() -> Self {
+        Self {
+            gamepads: BTreeMap::new(),
+            events: Vec::new(),
+            dead_zone: Self::DEFAULT_DEAD_ZONE,
+        }
+    }
+
+    /// Starts a new frame, pressed and released states and events are cleared.
+    pub(crate) fn step(&mut self) {
+        self.events.clear();
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.previous_buttons.clone_from(&gamepad.buttons);
+            gamepad.previous_axes.clone_from(&gamepad.axes);
+        }
+    }
+
+    /// Applies an event to the state of the current frame.
+    pub(crate) fn apply(&mut self, event: &GamepadEvent) {
+        match event {
+            GamepadEvent::Connected { id, name } => {
+                self.gamepads.entry(*id).or_default().name.clone_from(name);
+            }
+            GamepadEvent::Disconnected(id) => {
+                self.gamepads.remove(id);
+            }
+            GamepadEvent::ButtonPressed(id, button) => {
+                self.gamepads.entry(*id).or_default().buttons.insert(*button);
+            }
+            GamepadEvent::ButtonReleased(id, button) => {
+                self.gamepads.entry(*id).or_default().buttons.remove(button);
+            }
+            GamepadEvent::AxisChanged(id, axis, value) => {
+                self.gamepads.entry(*id).or_default().axes.insert(*axis, *value);
+            }
+        }
+        self.events.push(event.clone());
+    }
+
+    /// Returns ids of connected gamepads.
+    pub fn ids(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gamepads.keys().copied()
+    }
+
+    /// Returns true if a gamepad is connected.
+    pub fn is_connected(&self, id: GamepadId) -> bool {
+        self.gamepads.contains_key(&id)
+    }
+
+    /// Returns a name of a connected gamepad.
+    pub fn name(&self, id: GamepadId) -> Option<&str> {
+        self.gamepads.get(&id).map(|gamepad| gamepad.name.as_str())
+    }
+
+    /// Returns events applied since the last update.
+    pub fn events(&self) -> &[GamepadEvent] {
+        &self.events
+    }
+
+    /// Returns ids of gamepads connected since the last update.
+    pub fn just_connected(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.events.iter().filter_map(|event| match event {
+            GamepadEvent::Connected { id, .. } => Some(*id),
+            _ => None,
+        })
+    }
+
+    /// Returns ids of gamepads disconnected since the last update.
+    pub fn just_disconnected(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.events.iter().filter_map(|event| match event {
+            GamepadEvent::Disconnected(id) => Some(*id),
+            _ => None,
+        })
+    }
+
+    /// Returns its dead zone.
+    pub fn dead_zone(&self) -> f32 {
+        self.dead_zone
+    }
+
+    /// Sets its dead zone, a portion of the range around rest in 0..1.
+    /// Values inside are reported as 0, values outside are rescaled to start at 0.
+    pub fn set_dead_zone(&mut self, dead_zone: f32) {
+        self.dead_zone = dead_zone.clamp(0.0, 0.99);
+    }
+
+    /// Returns true if a gamepad button was pressed.
+    pub fn button_pressed(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads.get(&id).is_some_and(|gamepad| {
+            gamepad.buttons.contains(&button) && !gamepad.previous_buttons.contains(&button)
+        })
+    }
+
+    /// Returns true if a gamepad button is held.
+    pub fn button_held(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&id)
+            .is_some_and(|gamepad| gamepad.buttons.contains(&button))
+    }
+
+    /// Returns true if a gamepad button was released.
+    pub fn button_released(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads.get(&id).is_some_and(|gamepad| {
+            !gamepad.buttons.contains(&button) && gamepad.previous_buttons.contains(&button)
+        })
+    }
+
+    /// Returns a value of an axis with the dead zone applied.
+    pub fn axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        apply_dead_zone(self.raw_axis(id, axis), self.dead_zone)
+    }
+
+    /// Returns a value of an axis in the previous update with the dead zone applied.
+    pub fn previous_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        let value = self
+            .gamepads
+            .get(&id)
+            .and_then(|gamepad| gamepad.previous_axes.get(&axis))
+            .copied()
+            .unwrap_or(0.0);
+        apply_dead_zone(value, self.dead_zone)
+    }
+
+    /// Returns a value of an axis as reported by the gamepad.
+    pub fn raw_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepads
+            .get(&id)
+            .and_then(|gamepad| gamepad.axes.get(&axis))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Returns a position of the left stick, its length is at most 1.
+    /// The dead zone is applied to the length, so diagonals are not snapped to axes.
+    pub fn left_stick(&self, id: GamepadId) -> glam::Vec2 {
+        self.stick(id, GamepadAxis::LeftStickX, GamepadAxis::LeftStickY)
+    }
+
+    /// Returns a position of the right stick, its length is at most 1.
+    /// The dead zone is applied to the length, so diagonals are not snapped to axes.
+    pub fn right_stick(&self, id: GamepadId) -> glam::Vec2 {
+        self.stick(id, GamepadAxis::RightStickX, GamepadAxis::RightStickY)
+    }
+
+    fn stick(&self, id: GamepadId, x: GamepadAxis, y: GamepadAxis) -> glam::Vec2 {
+        let position = glam::vec2(self.raw_axis(id, x), self.raw_axis(id, y));
+        let length = position.length();
+        if length <= self.dead_zone {
+            return glam::Vec2::ZERO;
+        }
+
+        position * (apply_dead_zone(length, self.dead_zone) / length)
+    }
+}
+
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= dead_zone {
+        0.0
+    } else {
+        value.signum() * ((magnitude - dead_zone) / (1.0 - dead_zone)).min(1.0)
+    }
+}
+
+/// GamepadBackend of connected controllers using the gilrs crate.
+#[cfg(feature = "gilrs")]
+pub struct GilrsBackend {
+    gilrs: gilrs::Gilrs,
+    pending: Vec<GamepadEvent>,
+}
+
+#[cfg(feature = "gilrs")]
+impl GilrsBackend {
+    /// Creates a new GilrsBackend, gamepads connected already are reported by the first poll.
+    pub fn new() -> crate::Result<Self> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| crate::Error::GamepadInit(e.to_string()))?;
+        let pending = gilrs
+            .gamepads()
+            .map(|(id, gamepad)| GamepadEvent::Connected {
+                id: id.into(),
+                name: gamepad.name().to_string(),
+            })
+            .collect();
+
+        Ok(Self { gilrs, pending })
+    }
+
+    fn button(button: gilrs::Button) -> Option<GamepadButton> {
+        use gilrs::Button;
+
+        Some(match button {
+            Button::South => GamepadButton::South,
+            Button::East => GamepadButton::East,
+            Button::North => GamepadButton::North,
+            Button::West => GamepadButton::West,
+            Button::LeftTrigger => GamepadButton::LeftBumper,
+            Button::RightTrigger => GamepadButton::RightBumper,
+            Button::LeftThumb => GamepadButton::LeftStick,
+            Button::RightThumb => GamepadButton::RightStick,
+            Button::Select => GamepadButton::Select,
+            Button::Start => GamepadButton::Start,
+            Button::Mode => GamepadButton::Mode,
+            Button::DPadUp => GamepadButton::DPadUp,
+            Button::DPadDown => GamepadButton::DPadDown,
+            Button::DPadLeft => GamepadButton::DPadLeft,
+            Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+
+    fn axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+        use gilrs::Axis;
+
+        Some(match axis {
+            Axis::LeftStickX => GamepadAxis::LeftStickX,
+            Axis::LeftStickY => GamepadAxis::LeftStickY,
+            Axis::RightStickX => GamepadAxis::RightStickX,
+            Axis::RightStickY => GamepadAxis::RightStickY,
+            _ => return None,
+        })
+    }
+
+    fn trigger(button: gilrs::Button) -> Option<GamepadAxis> {
+        match button {
+            gilrs::Button::LeftTrigger2 => Some(GamepadAxis::LeftTrigger),
+            gilrs::Button::RightTrigger2 => Some(GamepadAxis::RightTrigger),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "gilrs")]
+impl GamepadBackend for GilrsBackend {
+    fn poll(&mut self, events: &mut Vec<GamepadEvent>) {
+        use gilrs::EventType;
+
+        events.append(&mut self.pending);
+        while let Some(gilrs::Event { id: gilrs_id, event, .. }) = self.gilrs.next_event() {
+            let id: GamepadId = gilrs_id.into();
+            let event = match event {
+                EventType::Connected => Some(GamepadEvent::Connected {
+                    id,
+                    name: self.gilrs.gamepad(gilrs_id).name().to_string(),
+                }),
+                EventType::Disconnected => Some(GamepadEvent::Disconnected(id)),
+                EventType::ButtonPressed(button, _) => {
+                    Self::button(button).map(|button| GamepadEvent::ButtonPressed(id, button))
+                }
+                EventType::ButtonReleased(button, _) => {
+                    Self::button(button).map(|button| GamepadEvent::ButtonReleased(id, button))
+                }
+                EventType::ButtonChanged(button, value, _) => {
+                    Self::trigger(button).map(|axis| GamepadEvent::AxisChanged(id, axis, value))
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    Self::axis(axis).map(|axis| GamepadEvent::AxisChanged(id, axis, value))
+                }
+                _ => None,
+            };
+            events.extend(event);
+        }
+    }
+}
@@ -1,51 +1,321 @@
 pub struct Input {
     pub(crate) input_helper: winit_input_helper::WinitInputHelper,
-    pub(crate) mouse_lock: bool,
+    pub(crate) cursor_mode: CursorMode,
+    pub(crate) cursor_mode_changed: bool,
     pub(crate) mouse_delta: (f32, f32),
-    pub(crate) scroll_delta: f32,
+    pub(crate) scroll_delta: (f32, f32),
+    pub(crate) mouse_held: [bool; Input::MOUSE_BUTTON_COUNT],
+    pub(crate) mouse_actions: Vec<(usize, bool)>,
+    pub(crate) redraw_requested: bool,
+    pub(crate) text: String,
+    pub(crate) modifiers: Modifiers,
+    pub(crate) gamepads: Gamepads,
+    pub(crate) gamepad_backend: Option<Box<dyn GamepadBackend>>,
+    pub(crate) touches: Touches,
+    pub(crate) gesture_recognizer: GestureRecognizer,
+    pub(crate) gestures: Vec<Gesture>,
+    pub(crate) focused_window: Option<WindowId>,
+    pub(crate) hovered_window: Option<WindowId>,
 }
 
-use super::Key;
+use super::{Key, Modifiers, MouseButton, WindowId};
+use super::{GamepadBackend, GamepadEvent, Gamepads, RecordedEvent, RecordedFrame};
+use super::{Gesture, GestureRecognizer, TouchEvent, TouchId, TouchPhase, Touches};
+
+/// How the cursor behaves in the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CursorMode {
+    /// The cursor is visible and moves freely.
+    Normal,
+    /// The cursor is hidden while it is over the window.
+    Hidden,
+    /// The cursor is visible and can't leave the window.
+    Confined,
+    /// The cursor is hidden and grabbed by the window, use mouse_delta for camera controls.
+    Locked,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Input {
     /// Creates a new struct.
-    pub(crate) fn new() -> Self {
+    /// Outside of a MainLoop it can be driven with begin_frame, the inject functions and end_frame.
+    pub fn new() -> Self {
         Self {
             input_helper: winit_input_helper::WinitInputHelper::new(),
-            mouse_lock: false,
+            cursor_mode: CursorMode::Normal,
+            cursor_mode_changed: false,
             mouse_delta: (0.0, 0.0),
-            scroll_delta: 0.0,
+            scroll_delta: (0.0, 0.0),
+            mouse_held: [false; Self::MOUSE_BUTTON_COUNT],
+            mouse_actions: Vec::new(),
+            redraw_requested: false,
+            text: String::new(),
+            modifiers: Modifiers::empty(),
+            gamepads: Gamepads::new(),
+            gamepad_backend: None,
+            touches: Touches::new(),
+            gesture_recognizer: GestureRecognizer::new(),
+            gestures: Vec::new(),
+            focused_window: None,
+            hovered_window: None,
         }
     }
 
     /// Updates itself with a winit event.
     pub(crate) fn update<T>(&mut self, event: &winit::event::Event<T>) {
-        self.input_helper.update(event);
-        if let winit::event::Event::DeviceEvent { device_id, event } = event {
-            match event {
-                winit::event::DeviceEvent::MouseMotion {
-                    delta
-                } => {
-                    self.mouse_delta.0 += delta.0 as f32;
-                    self.mouse_delta.1 += delta.1 as f32;
+        // WinitInputHelper indexes a table of 255 buttons with the number of other buttons.
+        let out_of_helper_range = matches!(
+            event,
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::MouseInput {
+                    button: MouseButton::Other(255..),
+                    ..
+                },
+                ..
+            }
+        );
+        if !out_of_helper_range {
+            self.input_helper.update(event);
+        }
+        match event {
+            winit::event::Event::NewEvents(_) => {
+                self.text.clear();
+                self.mouse_actions.clear();
+                self.gamepads.step();
+                self.touches.step();
+                self.gestures.clear();
+            }
+            winit::event::Event::WindowEvent { event, window_id } => match event {
+                winit::event::WindowEvent::ReceivedCharacter(character) if !character.is_control() => {
+                    self.text.push(*character);
                 }
-
-                winit::event::DeviceEvent::MouseWheel {
-                    delta
-                } => {
-                    if let winit::event::MouseScrollDelta::PixelDelta(position_delta) = delta {
-                        self.scroll_delta = position_delta.x as f32;
+                winit::event::WindowEvent::Touch(touch) => {
+                    let event = TouchEvent::from(touch);
+                    self.touches.apply(&event);
+                    self.gesture_recognizer.process(&event, &mut self.gestures);
+                }
+                winit::event::WindowEvent::MouseInput { state, button, .. } => {
+                    if let Some(index) = Self::button_index(*button) {
+                        let pressed = *state == winit::event::ElementState::Pressed;
+                        self.mouse_held[index] = pressed;
+                        self.mouse_actions.push((index, pressed));
                     }
                 }
-
+                winit::event::WindowEvent::ModifiersChanged(modifiers) => {
+                    self.modifiers = *modifiers;
+                }
+                winit::event::WindowEvent::CursorEntered { .. } => {
+                    self.hovered_window = Some(*window_id);
+                }
+                winit::event::WindowEvent::CursorLeft { .. } if self.hovered_window == Some(*window_id) => {
+                    self.hovered_window = None;
+                }
+                winit::event::WindowEvent::Focused(focused) => {
+                    self.modifiers = Modifiers::empty();
+                    if *focused {
+                        self.focused_window = Some(*window_id);
+                    } else if self.focused_window == Some(*window_id) {
+                        self.focused_window = None;
+                    }
+                    // Some platforms release a grabbed cursor when the window loses focus.
+                    if *focused {
+                        self.cursor_mode_changed = true;
+                    }
+                }
+                winit::event::WindowEvent::MouseWheel { delta, .. } => match delta {
+                    winit::event::MouseScrollDelta::LineDelta(x, y) => {
+                        self.scroll_delta.0 += x;
+                        self.scroll_delta.1 += y;
+                    }
+                    winit::event::MouseScrollDelta::PixelDelta(position_delta) => {
+                        self.scroll_delta.0 += (position_delta.x / Self::PIXELS_PER_LINE) as f32;
+                        self.scroll_delta.1 += (position_delta.y / Self::PIXELS_PER_LINE) as f32;
+                    }
+                },
                 _ => {}
+            },
+            winit::event::Event::DeviceEvent {
+                event: winit::event::DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                self.mouse_delta.0 += delta.0 as f32;
+                self.mouse_delta.1 += delta.1 as f32;
             }
+            _ => {}
         }
     }
 
+    /// Number of pixels of a pixel scroll delta (touchpads) counted as one line.
+    const PIXELS_PER_LINE: f64 = 38.0;
+
+    /// Feeds events of a recorded frame instead of live events.
+    pub fn replay_frame(&mut self, frame: &RecordedFrame) {
+        self.begin_frame();
+        for event in &frame.events {
+            self.inject(event);
+        }
+        self.update::<()>(&winit::event::Event::MainEventsCleared);
+    }
+
+    /// Starts a simulated frame.
+    /// Clears pressed and released states and deltas of the previous frame.
+    pub fn begin_frame(&mut self) {
+        self.updated();
+        self.update::<()>(&winit::event::Event::NewEvents(winit::event::StartCause::Poll));
+    }
+
+    /// Finishes a simulated frame, the state can be read by an update function now.
+    /// Events of the gamepad backend are polled here.
+    pub fn end_frame(&mut self) {
+        self.poll_gamepads();
+        self.update::<()>(&winit::event::Event::MainEventsCleared);
+    }
+
+    /// Injects a synthetic event into the current frame.
+    pub fn inject(&mut self, event: &RecordedEvent) {
+        match event {
+            RecordedEvent::Gamepad(event) => self.gamepads.apply(event),
+            event => self.update(&event.to_event().expect("only gamepad events have no winit event")),
+        }
+    }
+
+    /// Sets a source of gamepad events, it is polled once per frame.
+    pub fn set_gamepad_backend(&mut self, backend: Box<dyn GamepadBackend>) {
+        self.gamepad_backend = Some(backend);
+    }
+
+    /// Applies events of the gamepad backend to the current frame.
+    pub(crate) fn poll_gamepads(&mut self) {
+        let Some(backend) = &mut self.gamepad_backend else {
+            return;
+        };
+
+        let mut events: Vec<GamepadEvent> = Vec::new();
+        backend.poll(&mut events);
+        if !events.is_empty() {
+            self.redraw_requested = true;
+        }
+        for event in &events {
+            self.gamepads.apply(event);
+        }
+    }
+
+    /// Returns fingers touching the screen.
+    pub fn touches(&self) -> &Touches {
+        &self.touches
+    }
+
+    /// Returns gestures recognized since the last update.
+    pub fn gestures(&self) -> &[Gesture] {
+        &self.gestures
+    }
+
+    /// Returns a mutable reference to the gesture recognizer, used to change its settings.
+    pub fn gesture_recognizer_mut(&mut self) -> &mut GestureRecognizer {
+        &mut self.gesture_recognizer
+    }
+
+    /// Returns the state of connected gamepads.
+    pub fn gamepads(&self) -> &Gamepads {
+        &self.gamepads
+    }
+
+    /// Returns a mutable reference to the state of connected gamepads, used to change the dead zone.
+    pub fn gamepads_mut(&mut self) -> &mut Gamepads {
+        &mut self.gamepads
+    }
+
+    /// Injects a key press.
+    pub fn press_key(&mut self, key: Key) {
+        self.inject(&RecordedEvent::Key {
+            scancode: 0,
+            key: Some(key),
+            pressed: true,
+        });
+    }
+
+    /// Injects a key release.
+    pub fn release_key(&mut self, key: Key) {
+        self.inject(&RecordedEvent::Key {
+            scancode: 0,
+            key: Some(key),
+            pressed: false,
+        });
+    }
+
+    /// Injects typed text.
+    pub fn type_text(&mut self, text: &str) {
+        for character in text.chars() {
+            self.inject(&RecordedEvent::ReceivedCharacter(character));
+        }
+    }
+
+    /// Injects a change of modifier keys.
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.inject(&RecordedEvent::ModifiersChanged(modifiers));
+    }
+
+    /// Injects a mouse button press.
+    pub fn press_mouse(&mut self, button: MouseButton) {
+        self.inject(&RecordedEvent::MouseButton {
+            button,
+            pressed: true,
+        });
+    }
+
+    /// Injects a mouse button release.
+    pub fn release_mouse(&mut self, button: MouseButton) {
+        self.inject(&RecordedEvent::MouseButton {
+            button,
+            pressed: false,
+        });
+    }
+
+    /// Injects a raw mouse motion.
+    pub fn move_mouse(&mut self, x: f32, y: f32) {
+        self.inject(&RecordedEvent::MouseMotion {
+            x: x as f64,
+            y: y as f64,
+        });
+    }
+
+    /// Injects a mouse wheel scroll in lines.
+    pub fn scroll(&mut self, x: f32, y: f32) {
+        self.inject(&RecordedEvent::MouseWheel {
+            x,
+            y,
+            pixels: false,
+        });
+    }
+
+    /// Injects a touch event of a finger.
+    pub fn touch(&mut self, id: TouchId, phase: TouchPhase, x: f32, y: f32) {
+        self.inject(&RecordedEvent::Touch(TouchEvent::new(id, phase, x, y)));
+    }
+
+    /// Advances the clock used to recognize gestures by delta seconds.
+    /// MainLoop calls it with every update delta.
+    pub fn advance_time(&mut self, delta: f32) {
+        self.gesture_recognizer.advance(delta);
+    }
+
+    /// Injects a cursor movement to a position relative to the window.
+    pub fn set_cursor_position(&mut self, x: f32, y: f32) {
+        self.inject(&RecordedEvent::CursorMoved {
+            x: x as f64,
+            y: y as f64,
+        });
+    }
+
     /// This function should be called after every update function.
     pub(crate) fn updated(&mut self) {
         self.mouse_delta = (0.0, 0.0);
+        self.scroll_delta = (0.0, 0.0);
     }
 
     /// Returns true if a keyboard key was pressed.
@@ -63,13 +333,49 @@ impl Input {
         self.input_helper.key_held(key)
     }
 
+    /// Returns true if a keyboard key was pressed or repeated by the OS while held.
+    /// Should be used for keys editing text, such as Back or Left.
+    pub fn key_pressed_repeat(&self, key: Key) -> bool {
+        self.input_helper.key_pressed_os(key)
+    }
+
+    /// Returns text typed since the last update.
+    /// Contains characters after applying shift, dead keys, the keyboard layout
+    /// and committed IME compositions, control characters are left out.
+    /// Text comes from ReceivedCharacter events only, winit 0.26 does not report IME preedit,
+    /// so a composition in progress can't be shown and appears once it is committed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the state of modifier keys.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Returns true if a shift key is held.
+    pub fn shift(&self) -> bool {
+        self.modifiers.shift()
+    }
+
+    /// Returns true if a control key is held.
+    pub fn ctrl(&self) -> bool {
+        self.modifiers.ctrl()
+    }
+
+    /// Returns true if an alt key is held.
+    pub fn alt(&self) -> bool {
+        self.modifiers.alt()
+    }
+
+    /// Returns true if a logo key (Windows, Command) is held.
+    pub fn logo(&self) -> bool {
+        self.modifiers.logo()
+    }
+
     /// Returns true if a keyboard key was released.
     pub fn window_resized(&self) -> Option<(u32, u32)> {
-        if let Some(size) = self.input_helper.window_resized() {
-            return Some((size.width, size.height));
-        }
-
-        return None;
+        self.input_helper.window_resized().map(|size| (size.width, size.height))
     }
 
     /// Returns a mouse position delta relative to last update call.
@@ -79,25 +385,55 @@ impl Input {
         delta
     }
 
-    /// 
-    pub fn scroll_delta(&self) -> f32 {
-        self.input_helper.scroll_diff()
+    /// Returns a scroll delta (horizontal, vertical) in lines relative to last update call.
+    /// Pixel deltas of touchpads are converted to lines.
+    pub fn scroll_delta(&self) -> (f32, f32) {
+        self.scroll_delta
     }
 
-    /// Returns the mouse position relative to a window position.
+    /// Returns an id of the focused window, keyboard input belongs to it.
+    pub fn focused_window(&self) -> Option<WindowId> {
+        self.focused_window
+    }
+
+    /// Returns an id of the window under the cursor, mouse input belongs to it.
+    pub fn hovered_window(&self) -> Option<WindowId> {
+        self.hovered_window
+    }
+
+    /// Returns the mouse position relative to the hovered window position.
     /// Returns None if cursor is outside of a window.
     pub fn mouse_pos(&self) -> Option<(f32, f32)> {
         self.input_helper.mouse()
     }
 
-    /// Locks the mouse in the centre of window. 
+    /// Locks the mouse in the window and hides it.
+    /// Same as setting CursorMode::Locked or CursorMode::Normal.
     pub fn set_mouse_lock(&mut self, lock: bool) {
-        self.mouse_lock = lock;
+        self.set_cursor_mode(if lock { CursorMode::Locked } else { CursorMode::Normal });
+    }
+
+    /// Sets the cursor mode, it is applied to the window after the update function.
+    pub fn set_cursor_mode(&mut self, cursor_mode: CursorMode) {
+        if self.cursor_mode != cursor_mode {
+            self.cursor_mode = cursor_mode;
+            self.cursor_mode_changed = true;
+        }
+    }
+
+    /// Returns the cursor mode.
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    /// Requests another frame when the MainLoop redraws only on input.
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
     }
 
     /// Gets a state of the mouse lock;
     pub fn mouse_lock(&mut self) -> bool {
-        self.mouse_lock
+        self.cursor_mode == CursorMode::Locked
     }
 
     /// Returns true if the window will be closed.
@@ -112,17 +448,50 @@ impl Input {
     }
 
     /// Returns true if a mouse button was pressed.
-    pub fn mouse_pressed(&self, button: usize) -> bool {
-        self.input_helper.mouse_pressed(button)
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool {
+        Self::button_index(button).is_some_and(|index| self.mouse_actions.contains(&(index, true)))
     }
 
     /// Returns true if a mouse button was released.
-    pub fn mouse_released(&self, button: usize) -> bool {
-        self.input_helper.mouse_released(button)
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
+        Self::button_index(button).is_some_and(|index| self.mouse_actions.contains(&(index, false)))
     }
 
     /// Returns true if a mouse button was held.
-    pub fn mouse_held(&self, button: usize) -> bool {
-        self.input_helper.mouse_held(button)
+    pub fn mouse_held(&self, button: MouseButton) -> bool {
+        Self::button_index(button).is_some_and(|index| self.mouse_held[index])
+    }
+
+    /// Number of tracked mouse buttons, other buttons are ignored.
+    const MOUSE_BUTTON_COUNT: usize = 256;
+
+    /// Returns an index into the button table, other buttons follow the named ones.
+    /// WinitInputHelper is not used as it maps other buttons onto the named ones.
+    fn button_index(button: MouseButton) -> Option<usize> {
+        let index = match button {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Other(index) => index as usize + 3,
+        };
+        (index < Self::MOUSE_BUTTON_COUNT).then_some(index)
+    }
+
+    /// Applies a changed cursor mode to the window.
+    pub(crate) fn apply_cursor_mode(&mut self, window: &super::Window) {
+        if !std::mem::take(&mut self.cursor_mode_changed) {
+            return;
+        }
+
+        let (grab, visible) = match self.cursor_mode {
+            CursorMode::Normal => (false, true),
+            CursorMode::Hidden => (false, false),
+            CursorMode::Confined => (true, true),
+            CursorMode::Locked => (true, false),
+        };
+        if let Err(error) = window.set_cursor_grab(grab) {
+            log::warn!("failed to set cursor grab: {}", error);
+        }
+        window.set_cursor_visible(visible);
     }
 }
\ No newline at end of file
@@ -1,22 +1,65 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
 use std::time::{Instant};
 
-use winit::dpi::{Size, PhysicalSize};
 use winit::{
     event_loop::ControlFlow
 };
 
 use winit::event_loop::EventLoop;
-use winit::window::WindowBuilder;
 use winit::event::Event;
+use winit::event::StartCause;
 use winit::event::WindowEvent;
 
+use crate::{Error, Result};
+
 // -------- PUBLIC --------
 pub mod input;
 
 pub use input::*;
 
+pub mod fixed_timestep;
+
+pub use fixed_timestep::*;
+
+pub mod builder;
+
+pub use builder::*;
+
+pub mod pacing;
+
+pub use pacing::*;
+
+pub mod frame_stats;
+
+pub use frame_stats::*;
+
+pub mod action_map;
+
+pub use action_map::*;
+
+pub mod recording;
+
+pub use recording::*;
+
+pub mod gamepad;
+
+pub use gamepad::*;
+
+pub mod touch;
+
+pub use touch::*;
+
+pub mod windows;
+
+pub use windows::*;
+
 pub type Key = winit::event::VirtualKeyCode;
+pub type Modifiers = winit::event::ModifiersState;
+pub type MouseButton = winit::event::MouseButton;
 pub type Window = winit::window::Window;
+pub type WindowId = winit::window::WindowId;
+pub type WindowBuilder = winit::window::WindowBuilder;
 
 pub use crate::rendering::bundle::*;
 // ------------------------
@@ -25,30 +68,95 @@ pub use crate::rendering::bundle::*;
 /// Struct for controlling app loops and window creation.
 pub struct MainLoop {
     event_loop: EventLoop<()>,
-    window: Window,
+    windows: BTreeMap<WindowId, Arc<Window>>,
+    primary: WindowId,
     input: Input,
+    fixed_timestep: Option<FixedTimestep>,
+    frame_pacing: FramePacing,
+    vsync: bool,
+    input_recording: Option<(String, Recording)>,
+    input_replay: Option<std::vec::IntoIter<RecordedFrame>>,
 }
 
 impl MainLoop {
-    /// Creates new MainLoop.
+    /// Creates new MainLoop with an 800x600 window.
+    /// Use MainLoopBuilder to configure the window.
     pub fn new(window_title: &str) -> Self {
-        let event_loop = EventLoop::new();
-        let window = WindowBuilder::new()
-            .with_title(window_title)
-            .with_inner_size(Size::Physical(PhysicalSize::new(800, 600)))
-            .build(&event_loop)
-            .unwrap();
-
-        MainLoop {
-            event_loop,
-            window,
-            input: Input::new(),
-        }
+        MainLoopBuilder::new(window_title).build().unwrap()
+    }
+
+    /// Creates a new MainLoopBuilder.
+    pub fn builder(window_title: &str) -> MainLoopBuilder {
+        MainLoopBuilder::new(window_title)
+    }
+
+    /// Returns a reference to the primary window.
+    pub fn window(&self) -> &Arc<Window> {
+        &self.windows[&self.primary]
+    }
+
+    /// Creates a secondary window before the MainLoop runs.
+    /// Windows can be created later with Windows::create.
+    pub fn create_window(&mut self, builder: WindowBuilder) -> Result<WindowId> {
+        let window = builder.build(&self.event_loop).map_err(Error::WindowCreation)?;
+        let window_id = window.id();
+        self.windows.insert(window_id, Arc::new(window));
+        Ok(window_id)
+    }
+
+    /// Returns a reference to a window.
+    pub fn get_window(&self, window_id: WindowId) -> Option<&Arc<Window>> {
+        self.windows.get(&window_id)
+    }
+
+    /// Returns the vsync preference the MainLoop was built with.
+    /// Bundles created by Bundle::for_main_loop and Windows::bundle use it.
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    /// Returns its frame pacing.
+    pub fn frame_pacing(&self) -> &FramePacing {
+        &self.frame_pacing
+    }
+
+    /// Returns a mutable reference to its frame pacing.
+    pub fn frame_pacing_mut(&mut self) -> &mut FramePacing {
+        &mut self.frame_pacing
+    }
+
+    /// Sets its frame pacing.
+    pub fn set_frame_pacing(&mut self, frame_pacing: FramePacing) {
+        self.frame_pacing = frame_pacing;
+    }
+
+    /// Records all input and update deltas, the recording is saved into the path on exit.
+    pub fn record_input(&mut self, path: &str) {
+        self.input_recording = Some((path.to_string(), Recording::new()));
+    }
+
+    /// Replays a recording frame by frame instead of live input.
+    /// The loop exits after the last recorded frame.
+    /// Use Recording::replay to replay it without a window.
+    pub fn replay_input(&mut self, recording: Recording) {
+        self.input_replay = Some(recording.frames().to_vec().into_iter());
+    }
+
+    /// Sets a source of gamepad events.
+    /// With the gilrs feature, connected controllers are used by default.
+    pub fn set_gamepad_backend(&mut self, backend: Box<dyn GamepadBackend>) {
+        self.input.set_gamepad_backend(backend);
+    }
+
+    /// Returns its fixed timestep.
+    pub fn fixed_timestep(&self) -> Option<&FixedTimestep> {
+        self.fixed_timestep.as_ref()
     }
 
-    /// Returns a reference to the window.
-    pub fn window(&self) -> &Window {
-        &self.window
+    /// Sets its fixed timestep.
+    /// If set, App::fixed_update is called zero or more times per frame with a constant delta.
+    pub fn set_fixed_timestep(&mut self, fixed_timestep: Option<FixedTimestep>) {
+        self.fixed_timestep = fixed_timestep;
     }
 
     /// Run event loop of an app.
@@ -56,53 +164,204 @@ impl MainLoop {
         env_logger::init();
 
         let mut last = Instant::now();
+        let mut alpha = 1.0;
+        let mut focused_window = Some(self.primary);
+        let mut paused = false;
+        let mut woken = true;
+        let mut stats = FrameStats::default();
+        let mut closing = Vec::new();
 
-        self.event_loop.run(move |event, _, control_flow| {
-            self.input.update(&event);
+        self.event_loop.run(move |event, target, control_flow| {
+            let mut windows = Windows {
+                windows: &mut self.windows,
+                closing: &mut closing,
+                primary: self.primary,
+                vsync: self.vsync,
+                target,
+            };
+
+            if self.input_replay.is_none() {
+                self.input.update(&event);
+                if let Some((_, recording)) = &mut self.input_recording {
+                    recording.record_event(&event);
+                }
+            }
+            app.event(&mut windows, &event);
             
             match event {
+                Event::NewEvents(StartCause::Init) => {
+                    app.init(&mut windows);
+                },
                 Event::WindowEvent {
                     ref event,
                     window_id,
-                } if window_id == self.window.id() => match event {
-                    WindowEvent::CloseRequested => { 
-                        *control_flow = ControlFlow::Exit;
-                    },
+                } if windows.get(window_id).is_some() => {
+                    woken = true;
+                    match event {
+                        WindowEvent::CloseRequested => { 
+                            windows.close(window_id);
+                        },
+                        WindowEvent::Resized(size) => {
+                            app.resized(&mut windows, window_id, size.width, size.height);
+                        },
+                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                            app.resized(&mut windows, window_id, new_inner_size.width, new_inner_size.height);
+                        },
+                        WindowEvent::Focused(is_focused) => {
+                            if *is_focused {
+                                focused_window = Some(window_id);
+                            } else if focused_window == Some(window_id) {
+                                focused_window = None;
+                            }
+                            app.focus_changed(&mut windows, window_id, *is_focused);
+                        },
 
-                    _ => {}
+                        _ => {}
+                    }
+                },
+                Event::Suspended => {
+                    app.suspended(&mut windows);
+                },
+                Event::Resumed => {
+                    app.resumed(&mut windows);
+                },
+                Event::LoopDestroyed => {
+                    app.exiting(&mut windows);
+                    if let Some((path, recording)) = &self.input_recording {
+                        if let Err(error) = recording.save(path) {
+                            log::error!("{}", error);
+                        }
+                    }
                 },
-                Event::RedrawRequested(window_id) if window_id == self.window.id() => {
-                    app.render();                   
+                Event::RedrawRequested(window_id) if windows.get(window_id).is_some() => {
+                    let render_start = Instant::now();
+                    app.render(&mut windows, window_id, alpha);
+                    if window_id == self.primary {
+                        stats.set_render_time(render_start.elapsed().as_secs_f32());
+                    }
                 },
                 Event::MainEventsCleared => {
-                    if self.input.mouse_lock {
-                        let new_pos = winit::dpi::PhysicalPosition::new(self.window.inner_size().width / 2,
-                            self.window.inner_size().height / 2);
-                        self.window.set_cursor_position(new_pos).unwrap();
+                    if self.input_replay.is_none() {
+                        self.input.poll_gamepads();
+                        if let Some((_, recording)) = &mut self.input_recording {
+                            for event in self.input.gamepads().events() {
+                                recording.record_gamepad_event(event);
+                            }
+                        }
                     }
-                    
+
+                    let woken_by_input = std::mem::take(&mut woken)
+                        || std::mem::take(&mut self.input.redraw_requested);
+
+                    if self.frame_pacing.idle_when_unfocused() && focused_window.is_none() {
+                        paused = true;
+                        *control_flow = ControlFlow::Wait;
+                        return;
+                    }
+                    if paused {
+                        // Do not count the time spent unfocused or waiting for input into the delta.
+                        paused = false;
+                        last = Instant::now();
+                    }
+
+                    if self.frame_pacing.redraw_mode() == RedrawMode::OnInput {
+                        *control_flow = ControlFlow::Wait;
+                        if !woken_by_input {
+                            paused = true;
+                            return;
+                        }
+                    } else {
+                        *control_flow = ControlFlow::Poll;
+                    }
+
+                    if let Some(duration) = self.frame_pacing.sleep_duration(last, Instant::now()) {
+                        std::thread::sleep(duration);
+                    }
+
                     // UPDATE
                     let now = Instant::now();
-                    let delta = now.duration_since(last).as_micros() as f32 / 1000000.0;
-                    app.update(&mut self.window, delta, &mut self.input);
+                    let mut delta = now.duration_since(last).as_micros() as f32 / 1000000.0;
+                    if let Some(replay) = &mut self.input_replay {
+                        match replay.next() {
+                            Some(frame) => {
+                                self.input.replay_frame(&frame);
+                                delta = frame.delta;
+                            },
+                            None => {
+                                *control_flow = ControlFlow::Exit;
+                                return;
+                            },
+                        }
+                    }
+                    if let Some((_, recording)) = &mut self.input_recording {
+                        recording.end_frame(delta);
+                    }
+                    stats.add_frame(delta);
+                    self.input.advance_time(delta);
+                    if let Some(fixed_timestep) = &mut self.fixed_timestep {
+                        for _ in 0..fixed_timestep.advance(delta) {
+                            app.fixed_update(&mut windows, fixed_timestep.step(), &mut self.input);
+                        }
+                        alpha = fixed_timestep.alpha();
+                    }
+                    app.update(&mut windows, &stats, &mut self.input);
+                    stats.set_update_time(now.elapsed().as_secs_f32());
+                    self.input.apply_cursor_mode(windows.primary());
                     self.input.updated();
                     last = now;
                     
                     // RENDER
-                    self.window.request_redraw();
+                    for window in windows.windows.values() {
+                        window.request_redraw();
+                    }
                 },
 
                 _ => {}
             }
 
+            // Hooks called for a closed window can close further windows.
+            while let Some(window_id) = windows.closing.pop() {
+                if window_id == windows.primary {
+                    *control_flow = ControlFlow::Exit;
+                } else {
+                    app.window_closed(&mut windows, window_id);
+                    windows.windows.remove(&window_id);
+                    if focused_window == Some(window_id) {
+                        focused_window = None;
+                    }
+                }
+            }
         });
     }
 }
 
 /// Trait for creating struct which can be controlled by an event loop.
 pub trait App {
-    /// Update function.
-    fn update(&mut self, window: &mut Window, delta: f32, input: &mut Input);
-    /// Render function.
-    fn render(&mut self);
+    /// Called once before the first update.
+    fn init(&mut self, _windows: &mut Windows) {}
+    /// Called when a window has been resized, size is in physical pixels.
+    fn resized(&mut self, _windows: &mut Windows, _window_id: WindowId, _width: u32, _height: u32) {}
+    /// Called when a window gains or loses focus.
+    fn focus_changed(&mut self, _windows: &mut Windows, _window_id: WindowId, _focused: bool) {}
+    /// Called when a secondary window is closed, its Bundle should be dropped here as it keeps the window alive.
+    /// The primary window is not closed this way, the MainLoop exits instead.
+    fn window_closed(&mut self, _windows: &mut Windows, _window_id: WindowId) {}
+    /// Called when the app is suspended (mobile platforms).
+    fn suspended(&mut self, _windows: &mut Windows) {}
+    /// Called when the app is resumed (mobile platforms).
+    fn resumed(&mut self, _windows: &mut Windows) {}
+    /// Called once when the event loop is exiting, last chance to save a state.
+    fn exiting(&mut self, _windows: &mut Windows) {}
+    /// Called with every winit event before it is handled by the MainLoop.
+    fn event(&mut self, _windows: &mut Windows, _event: &Event<()>) {}
+    /// Update function, called once per frame for all windows.
+    /// The time since the last update is stats.delta().
+    fn update(&mut self, windows: &mut Windows, stats: &FrameStats, input: &mut Input);
+    /// Fixed update function, called only if the MainLoop has a fixed timestep.
+    /// Delta is always the duration of one step.
+    fn fixed_update(&mut self, _windows: &mut Windows, _delta: f32, _input: &mut Input) {}
+    /// Render function, called once per frame for every window.
+    /// Alpha is the interpolation factor between the last two fixed updates,
+    /// it is always 1.0 without a fixed timestep.
+    fn render(&mut self, windows: &mut Windows, window_id: WindowId, alpha: f32);
 }
@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+/// When a MainLoop runs update and render.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RedrawMode {
+    /// A new frame is run as soon as possible (or at the target FPS).
+    Continuous,
+    /// A new frame is run only after a window event, such as input or resize,
+    /// or after Input::request_redraw has been called. The loop sleeps otherwise.
+    OnInput,
+}
+
+/// Options for how often a MainLoop runs frames.
+#[derive(Clone, Copy, Debug)]
+pub struct FramePacing {
+    target_fps: Option<f32>,
+    idle_when_unfocused: bool,
+    redraw_mode: RedrawMode,
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        Self {
+            target_fps: None,
+            idle_when_unfocused: false,
+            redraw_mode: RedrawMode::Continuous,
+        }
+    }
+}
+
+impl FramePacing {
+    /// Creates a new FramePacing which runs frames continuously without a cap.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns its target FPS.
+    pub fn target_fps(&self) -> Option<f32> {
+        self.target_fps
+    }
+
+    /// Sets its target FPS, None for no cap.
+    /// The MainLoop sleeps between frames to not exceed it.
+    pub fn set_target_fps(&mut self, target_fps: Option<f32>) {
+        self.target_fps = target_fps;
+    }
+
+    /// Returns true if no frames are run while the window is unfocused.
+    pub fn idle_when_unfocused(&self) -> bool {
+        self.idle_when_unfocused
+    }
+
+    /// Sets whether no frames are run while the window is unfocused.
+    pub fn set_idle_when_unfocused(&mut self, idle_when_unfocused: bool) {
+        self.idle_when_unfocused = idle_when_unfocused;
+    }
+
+    /// Returns its redraw mode.
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.redraw_mode
+    }
+
+    /// Sets its redraw mode.
+    pub fn set_redraw_mode(&mut self, redraw_mode: RedrawMode) {
+        self.redraw_mode = redraw_mode;
+    }
+
+    /// Returns the minimal duration of a frame.
+    pub fn frame_time(&self) -> Option<Duration> {
+        self.target_fps
+            .filter(|fps| *fps > 0.0)
+            .map(|fps| Duration::from_secs_f32(1.0 / fps))
+    }
+
+    /// Returns how long to sleep at now before the frame after the one started at last, None to run it right away.
+    /// Without a target FPS frames are not capped, with vsync presenting the frame waits for the display instead.
+    pub fn sleep_duration(&self, last: Instant, now: Instant) -> Option<Duration> {
+        let deadline = last + self.frame_time()?;
+        deadline.checked_duration_since(now).filter(|duration| !duration.is_zero())
+    }
+}
@@ -0,0 +1,276 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use winit::event::{DeviceEvent, ElementState, Event, MouseScrollDelta, WindowEvent};
+
+use super::{FrameStats, GamepadEvent, Input, Key, MouseButton, TouchEvent};
+use crate::{Error, Result};
+
+/// A winit event consumed by Input, in a form which can be saved and replayed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedEvent {
+    Key {
+        scancode: u32,
+        key: Option<Key>,
+        pressed: bool,
+    },
+    MouseButton {
+        button: MouseButton,
+        pressed: bool,
+    },
+    CursorMoved {
+        x: f64,
+        y: f64,
+    },
+    CursorEntered,
+    CursorLeft,
+    /// Scroll in lines (pixels is false) or in pixels.
+    MouseWheel {
+        x: f32,
+        y: f32,
+        pixels: bool,
+    },
+    /// Raw mouse motion of a device.
+    MouseMotion {
+        x: f64,
+        y: f64,
+    },
+    ReceivedCharacter(char),
+    ModifiersChanged(winit::event::ModifiersState),
+    Resized {
+        width: u32,
+        height: u32,
+    },
+    Focused(bool),
+    DroppedFile(PathBuf),
+    CloseRequested,
+    Touch(TouchEvent),
+    /// An event of a GamepadBackend, it has no winit counterpart.
+    Gamepad(GamepadEvent),
+}
+
+impl RecordedEvent {
+    /// Converts a winit event, returns None for events Input does not use.
+    pub fn from_event<T>(event: &Event<T>) -> Option<Self> {
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::KeyboardInput { input, .. } => Some(Self::Key {
+                    scancode: input.scancode,
+                    key: input.virtual_keycode,
+                    pressed: input.state == ElementState::Pressed,
+                }),
+                WindowEvent::MouseInput { button, state, .. } => Some(Self::MouseButton {
+                    button: *button,
+                    pressed: *state == ElementState::Pressed,
+                }),
+                WindowEvent::CursorMoved { position, .. } => Some(Self::CursorMoved {
+                    x: position.x,
+                    y: position.y,
+                }),
+                WindowEvent::CursorEntered { .. } => Some(Self::CursorEntered),
+                WindowEvent::CursorLeft { .. } => Some(Self::CursorLeft),
+                WindowEvent::MouseWheel { delta, .. } => Some(Self::from_scroll_delta(delta)),
+                WindowEvent::ReceivedCharacter(character) => Some(Self::ReceivedCharacter(*character)),
+                WindowEvent::ModifiersChanged(modifiers) => Some(Self::ModifiersChanged(*modifiers)),
+                WindowEvent::Resized(size) => Some(Self::Resized {
+                    width: size.width,
+                    height: size.height,
+                }),
+                WindowEvent::Focused(focused) => Some(Self::Focused(*focused)),
+                WindowEvent::DroppedFile(path) => Some(Self::DroppedFile(path.clone())),
+                WindowEvent::CloseRequested => Some(Self::CloseRequested),
+                WindowEvent::Touch(touch) => Some(Self::Touch(touch.into())),
+                _ => None,
+            },
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => Some(Self::MouseMotion {
+                x: delta.0,
+                y: delta.1,
+            }),
+            _ => None,
+        }
+    }
+
+    fn from_scroll_delta(delta: &MouseScrollDelta) -> Self {
+        match delta {
+            MouseScrollDelta::LineDelta(x, y) => Self::MouseWheel {
+                x: *x,
+                y: *y,
+                pixels: false,
+            },
+            MouseScrollDelta::PixelDelta(position) => Self::MouseWheel {
+                x: position.x as f32,
+                y: position.y as f32,
+                pixels: true,
+            },
+        }
+    }
+
+    /// Converts back to a winit event with dummy window and device ids.
+    /// Returns None for gamepad events.
+    #[allow(deprecated)]
+    pub fn to_event(&self) -> Option<Event<'static, ()>> {
+        // SAFETY: dummy ids are only compared with each other, they are never passed to the OS.
+        let window_id = unsafe { winit::window::WindowId::dummy() };
+        let device_id = unsafe { winit::event::DeviceId::dummy() };
+        let modifiers = winit::event::ModifiersState::empty();
+        let state = |pressed: bool| {
+            if pressed {
+                ElementState::Pressed
+            } else {
+                ElementState::Released
+            }
+        };
+
+        let event = match self {
+            Self::Key {
+                scancode,
+                key,
+                pressed,
+            } => WindowEvent::KeyboardInput {
+                device_id,
+                input: winit::event::KeyboardInput {
+                    scancode: *scancode,
+                    state: state(*pressed),
+                    virtual_keycode: *key,
+                    modifiers,
+                },
+                is_synthetic: false,
+            },
+            Self::MouseButton { button, pressed } => WindowEvent::MouseInput {
+                device_id,
+                state: state(*pressed),
+                button: *button,
+                modifiers,
+            },
+            Self::CursorMoved { x, y } => WindowEvent::CursorMoved {
+                device_id,
+                position: winit::dpi::PhysicalPosition::new(*x, *y),
+                modifiers,
+            },
+            Self::CursorEntered => WindowEvent::CursorEntered { device_id },
+            Self::CursorLeft => WindowEvent::CursorLeft { device_id },
+            Self::MouseWheel { x, y, pixels } => WindowEvent::MouseWheel {
+                device_id,
+                delta: if *pixels {
+                    MouseScrollDelta::PixelDelta(winit::dpi::PhysicalPosition::new(*x as f64, *y as f64))
+                } else {
+                    MouseScrollDelta::LineDelta(*x, *y)
+                },
+                phase: winit::event::TouchPhase::Moved,
+                modifiers,
+            },
+            Self::MouseMotion { x, y } => {
+                return Some(Event::DeviceEvent {
+                    device_id,
+                    event: DeviceEvent::MouseMotion { delta: (*x, *y) },
+                })
+            }
+            Self::ReceivedCharacter(character) => WindowEvent::ReceivedCharacter(*character),
+            Self::ModifiersChanged(modifiers) => WindowEvent::ModifiersChanged(*modifiers),
+            Self::Resized { width, height } => {
+                WindowEvent::Resized(winit::dpi::PhysicalSize::new(*width, *height))
+            }
+            Self::Focused(focused) => WindowEvent::Focused(*focused),
+            Self::DroppedFile(path) => WindowEvent::DroppedFile(path.clone()),
+            Self::CloseRequested => WindowEvent::CloseRequested,
+            Self::Touch(touch) => WindowEvent::Touch(winit::event::Touch {
+                device_id,
+                phase: touch.phase,
+                location: winit::dpi::PhysicalPosition::new(touch.x as f64, touch.y as f64),
+                force: None,
+                id: touch.id,
+            }),
+            Self::Gamepad(_) => return None,
+        };
+
+        Some(Event::WindowEvent { window_id, event })
+    }
+}
+
+/// Events consumed during one frame and the delta passed to App::update.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub delta: f32,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// Input of consecutive frames, used to reproduce a session exactly.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    frames: Vec<RecordedFrame>,
+    #[serde(skip)]
+    pending: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    /// Creates a new empty Recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns its finished frames.
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Records an event into the current frame if Input uses it.
+    pub fn record_event<T>(&mut self, event: &Event<T>) {
+        if let Some(event) = RecordedEvent::from_event(event) {
+            self.pending.push(event);
+        }
+    }
+
+    /// Records an event of a GamepadBackend.
+    pub fn record_gamepad_event(&mut self, event: &GamepadEvent) {
+        self.pending.push(RecordedEvent::Gamepad(event.clone()));
+    }
+
+    /// Finishes the current frame with the delta passed to App::update.
+    pub fn end_frame(&mut self, delta: f32) {
+        self.frames.push(RecordedFrame {
+            delta,
+            events: std::mem::take(&mut self.pending),
+        });
+    }
+
+    /// Replays its frames into the input without a window or an event loop.
+    /// Update is called after every frame in the order of MainLoop::run, like App::update,
+    /// with stats whose delta is the recorded one.
+    pub fn replay(&self, input: &mut Input, mut update: impl FnMut(&FrameStats, &mut Input)) {
+        let mut stats = FrameStats::default();
+        for frame in &self.frames {
+            input.replay_frame(frame);
+            stats.add_frame(frame.delta);
+            input.advance_time(frame.delta);
+            update(&stats, input);
+            input.updated();
+        }
+    }
+
+    /// Parses a Recording from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(Error::RecordingParse)
+    }
+
+    /// Serializes the Recording into a JSON string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("recordings are always serializable")
+    }
+
+    /// Loads a Recording from a JSON file.
+    pub fn load(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path).map_err(|e| Error::missing_file(path, e))?;
+        Self::from_json(&json)
+    }
+
+    /// Saves the Recording into a JSON file.
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.to_json()).map_err(|e| Error::FileWrite {
+            path: path.into(),
+            source: e,
+        })
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Identifies a finger while it touches the screen.
+pub type TouchId = u64;
+pub type TouchPhase = winit::event::TouchPhase;
+
+/// A change of one finger, position is in physical pixels relative to the window.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TouchEvent {
+    pub id: TouchId,
+    pub phase: TouchPhase,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl TouchEvent {
+    /// Creates a new TouchEvent.
+    pub fn new(id: TouchId, phase: TouchPhase, x: f32, y: f32) -> Self {
+        Self { id, phase, x, y }
+    }
+
+    /// Returns its position.
+    pub fn position(&self) -> glam::Vec2 {
+        glam::vec2(self.x, self.y)
+    }
+}
+
+impl From<&winit::event::Touch> for TouchEvent {
+    fn from(touch: &winit::event::Touch) -> Self {
+        Self::new(touch.id, touch.phase, touch.location.x as f32, touch.location.y as f32)
+    }
+}
+
+/// A finger touching the screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Touch {
+    pub id: TouchId,
+    /// Phase of the last event of the finger.
+    pub phase: TouchPhase,
+    pub position: glam::Vec2,
+    pub start_position: glam::Vec2,
+    /// Movement since the last update.
+    pub delta: glam::Vec2,
+}
+
+/// State of fingers touching the screen, updated once per frame.
+#[derive(Clone, Debug, Default)]
+pub struct Touches {
+    touches: BTreeMap<TouchId, Touch>,
+    events: Vec<TouchEvent>,
+}
+
+impl Touches {
+    /// Creates a new struct without any touches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new frame, touches which ended in the previous frame are removed.
+    pub(crate) fn step(&mut self) {
+        self.events.clear();
+        self.touches
+            .retain(|_, touch| matches!(touch.phase, TouchPhase::Started | TouchPhase::Moved));
+        for touch in self.touches.values_mut() {
+            touch.delta = glam::Vec2::ZERO;
+        }
+    }
+
+    /// Applies an event to the state of the current frame.
+    pub(crate) fn apply(&mut self, event: &TouchEvent) {
+        let position = event.position();
+        let touch = self.touches.entry(event.id).or_insert(Touch {
+            id: event.id,
+            phase: event.phase,
+            position,
+            start_position: position,
+            delta: glam::Vec2::ZERO,
+        });
+        touch.phase = event.phase;
+        touch.delta += position - touch.position;
+        touch.position = position;
+        self.events.push(*event);
+    }
+
+    /// Returns touches, including the ones which ended since the last update.
+    pub fn iter(&self) -> impl Iterator<Item = &Touch> {
+        self.touches.values()
+    }
+
+    /// Returns a touch of a finger.
+    pub fn get(&self, id: TouchId) -> Option<&Touch> {
+        self.touches.get(&id)
+    }
+
+    /// Returns the number of fingers touching the screen.
+    pub fn active_count(&self) -> usize {
+        self.iter()
+            .filter(|touch| matches!(touch.phase, TouchPhase::Started | TouchPhase::Moved))
+            .count()
+    }
+
+    /// Returns events applied since the last update.
+    pub fn events(&self) -> &[TouchEvent] {
+        &self.events
+    }
+}
+
+/// A gesture recognized from touch events.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    /// A short touch of one finger.
+    Tap { position: glam::Vec2 },
+    /// A tap shortly after another one at the same place, it is reported instead of the second Tap.
+    DoubleTap { position: glam::Vec2 },
+    /// Two fingers moved closer together (scale < 1) or further apart (scale > 1).
+    /// Scale is relative to the previous distance of the fingers.
+    Pinch { center: glam::Vec2, scale: f32 },
+    /// Two fingers moved together, delta is the movement of their center.
+    Pan { delta: glam::Vec2 },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct TrackedTouch {
+    position: glam::Vec2,
+    start_position: glam::Vec2,
+    start_time: f32,
+    /// False once the touch can't be a tap anymore.
+    tap: bool,
+}
+
+/// Recognizes gestures from a stream of touch events.
+/// Its clock is advanced by advance, so it does not depend on the real time.
+#[derive(Clone, Debug)]
+pub struct GestureRecognizer {
+    touches: BTreeMap<TouchId, TrackedTouch>,
+    last_tap: Option<(f32, glam::Vec2)>,
+    time: f32,
+    tap_time: f32,
+    tap_distance: f32,
+    double_tap_time: f32,
+    double_tap_distance: f32,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureRecognizer {
+    pub const DEFAULT_TAP_TIME: f32 = 0.3;
+    pub const DEFAULT_TAP_DISTANCE: f32 = 10.0;
+    pub const DEFAULT_DOUBLE_TAP_TIME: f32 = 0.3;
+    pub const DEFAULT_DOUBLE_TAP_DISTANCE: f32 = 40.0;
+
+    /// Creates a new GestureRecognizer with default settings.
+    pub fn new() -> Self {
+        Self {
+            touches: BTreeMap::new(),
+            last_tap: None,
+            time: 0.0,
+            tap_time: Self::DEFAULT_TAP_TIME,
+            tap_distance: Self::DEFAULT_TAP_DISTANCE,
+            double_tap_time: Self::DEFAULT_DOUBLE_TAP_TIME,
+            double_tap_distance: Self::DEFAULT_DOUBLE_TAP_DISTANCE,
+        }
+    }
+
+    /// Returns the longest duration of a tap in seconds.
+    pub fn tap_time(&self) -> f32 {
+        self.tap_time
+    }
+
+    /// Sets the longest duration of a tap in seconds.
+    pub fn set_tap_time(&mut self, tap_time: f32) {
+        self.tap_time = tap_time;
+    }
+
+    /// Returns the longest distance a finger can move during a tap, in pixels.
+    pub fn tap_distance(&self) -> f32 {
+        self.tap_distance
+    }
+
+    /// Sets the longest distance a finger can move during a tap, in pixels.
+    pub fn set_tap_distance(&mut self, tap_distance: f32) {
+        self.tap_distance = tap_distance;
+    }
+
+    /// Returns the longest time between the end of a tap and the start of a double tap in seconds.
+    pub fn double_tap_time(&self) -> f32 {
+        self.double_tap_time
+    }
+
+    /// Sets the longest time between the end of a tap and the start of a double tap in seconds.
+    pub fn set_double_tap_time(&mut self, double_tap_time: f32) {
+        self.double_tap_time = double_tap_time;
+    }
+
+    /// Returns the longest distance between two taps of a double tap, in pixels.
+    pub fn double_tap_distance(&self) -> f32 {
+        self.double_tap_distance
+    }
+
+    /// Sets the longest distance between two taps of a double tap, in pixels.
+    pub fn set_double_tap_distance(&mut self, double_tap_distance: f32) {
+        self.double_tap_distance = double_tap_distance;
+    }
+
+    /// Advances its clock by delta seconds.
+    pub fn advance(&mut self, delta: f32) {
+        self.time += delta;
+    }
+
+    /// Processes an event and appends recognized gestures.
+    pub fn process(&mut self, event: &TouchEvent, gestures: &mut Vec<Gesture>) {
+        let position = event.position();
+        match event.phase {
+            TouchPhase::Started => {
+                // A finger joining another one starts a two finger gesture, not taps.
+                let alone = self.touches.is_empty();
+                for touch in self.touches.values_mut() {
+                    touch.tap = false;
+                }
+                self.touches.insert(
+                    event.id,
+                    TrackedTouch {
+                        position,
+                        start_position: position,
+                        start_time: self.time,
+                        tap: alone,
+                    },
+                );
+            }
+            TouchPhase::Moved => {
+                let previous = self.two_fingers();
+                let Some(touch) = self.touches.get_mut(&event.id) else {
+                    return;
+                };
+                touch.position = position;
+                if touch.position.distance(touch.start_position) > self.tap_distance {
+                    touch.tap = false;
+                }
+
+                if let (Some((previous_a, previous_b)), Some((a, b))) = (previous, self.two_fingers()) {
+                    let previous_distance = previous_a.distance(previous_b);
+                    let center = (a + b) * 0.5;
+                    if previous_distance > 0.0 {
+                        gestures.push(Gesture::Pinch {
+                            center,
+                            scale: a.distance(b) / previous_distance,
+                        });
+                    }
+                    gestures.push(Gesture::Pan {
+                        delta: center - (previous_a + previous_b) * 0.5,
+                    });
+                }
+            }
+            TouchPhase::Ended => {
+                let Some(touch) = self.touches.remove(&event.id) else {
+                    return;
+                };
+                let tap = touch.tap
+                    && self.time - touch.start_time <= self.tap_time
+                    && position.distance(touch.start_position) <= self.tap_distance;
+                if !tap {
+                    return;
+                }
+
+                match self.last_tap.take() {
+                    Some((time, last_position))
+                        if touch.start_time - time <= self.double_tap_time
+                            && position.distance(last_position) <= self.double_tap_distance =>
+                    {
+                        gestures.push(Gesture::DoubleTap { position });
+                    }
+                    _ => {
+                        self.last_tap = Some((self.time, position));
+                        gestures.push(Gesture::Tap { position });
+                    }
+                }
+            }
+            TouchPhase::Cancelled => {
+                self.touches.remove(&event.id);
+            }
+        }
+    }
+
+    /// Returns positions of both fingers if exactly two are touching.
+    fn two_fingers(&self) -> Option<(glam::Vec2, glam::Vec2)> {
+        let mut touches = self.touches.values();
+        match (touches.next(), touches.next(), touches.next()) {
+            (Some(a), Some(b), None) => Some((a.position, b.position)),
+            _ => None,
+        }
+    }
+}
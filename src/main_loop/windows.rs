@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use winit::event_loop::EventLoopWindowTarget;
+
+use super::{Bundle, Window, WindowBuilder, WindowId};
+use crate::{Error, Result};
+
+/// Windows of a MainLoop, passed to App hooks.
+/// The primary window is created by MainLoopBuilder, closing it exits the MainLoop.
+pub struct Windows<'a> {
+    pub(crate) windows: &'a mut BTreeMap<WindowId, Arc<Window>>,
+    pub(crate) closing: &'a mut Vec<WindowId>,
+    pub(crate) primary: WindowId,
+    pub(crate) vsync: bool,
+    pub(crate) target: &'a EventLoopWindowTarget<()>,
+}
+
+impl<'a> Windows<'a> {
+    /// Returns an id of the primary window.
+    pub fn primary_id(&self) -> WindowId {
+        self.primary
+    }
+
+    /// Returns a reference to the primary window.
+    pub fn primary(&self) -> &Arc<Window> {
+        &self.windows[&self.primary]
+    }
+
+    /// Returns a reference to a window, None if it has been closed.
+    pub fn get(&self, window_id: WindowId) -> Option<&Arc<Window>> {
+        self.windows.get(&window_id)
+    }
+
+    /// Returns ids of open windows.
+    pub fn ids(&self) -> impl Iterator<Item = WindowId> + '_ {
+        self.windows.keys().copied()
+    }
+
+    /// Returns the number of open windows.
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Returns true if no window is open, it can't happen while the MainLoop runs.
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Returns the vsync preference of the MainLoop.
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    /// Creates a Bundle for a window with the vsync preference of the MainLoop.
+    ///
+    /// # Panics
+    /// Panics if the window has been closed.
+    pub fn bundle(&self, window_id: WindowId) -> Result<Bundle> {
+        Bundle::for_window(&self.windows[&window_id], self.vsync)
+    }
+
+    /// Creates a secondary window, its Bundle can be created right away.
+    pub fn create(&mut self, builder: WindowBuilder) -> Result<WindowId> {
+        let window = builder.build(self.target).map_err(Error::WindowCreation)?;
+        let window_id = window.id();
+        self.windows.insert(window_id, Arc::new(window));
+        Ok(window_id)
+    }
+
+    /// Closes a window after the current hook returns, App::window_closed is called first.
+    /// Closing the primary window exits the MainLoop.
+    pub fn close(&mut self, window_id: WindowId) {
+        if self.windows.contains_key(&window_id) && !self.closing.contains(&window_id) {
+            self.closing.push(window_id);
+        }
+    }
+}
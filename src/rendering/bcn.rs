@@ -0,0 +1,773 @@
+//! Decoders of BC1-BC7 blocks, used when the device can't sample compressed textures.
+
+/// Decodes a color block of BC1, BC2 and BC3 into RGBA texels in row order.
+/// BC1 blocks with the first color not greater than the second have a transparent texel.
+fn decode_color(block: &[u8], punch_through: bool) -> [[u8; 4]; 16] {
+    let color0 = u16::from_le_bytes([block[0], block[1]]);
+    let color1 = u16::from_le_bytes([block[2], block[3]]);
+    let (a, b) = (rgb565(color0), rgb565(color1));
+
+    let mix = |weight_a: u16, weight_b: u16| {
+        let total = weight_a + weight_b;
+        let channel = |i: usize| ((a[i] as u16 * weight_a + b[i] as u16 * weight_b) / total) as u8;
+        [channel(0), channel(1), channel(2), 255]
+    };
+    let palette = if color0 > color1 || !punch_through {
+        [a, b, mix(2, 1), mix(1, 2)]
+    } else {
+        [a, b, mix(1, 1), [0, 0, 0, 0]]
+    };
+
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    let mut texels = [[0; 4]; 16];
+    for (i, texel) in texels.iter_mut().enumerate() {
+        *texel = palette[(indices >> (2 * i) & 0b11) as usize];
+    }
+    texels
+}
+
+fn rgb565(color: u16) -> [u8; 4] {
+    let r = (color >> 11) as u8 & 0x1f;
+    let g = (color >> 5) as u8 & 0x3f;
+    let b = color as u8 & 0x1f;
+    [r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2, 255]
+}
+
+/// Decodes a channel block of BC3 alpha, BC4 and BC5 into values of texels in row order.
+fn decode_channel(block: &[u8]) -> [u8; 16] {
+    channel_values(block, block[0] as i32, block[1] as i32, 0, 255).map(|value| value as u8)
+}
+
+/// Decodes a channel block of BC4 and BC5 snorm formats into signed values of texels in row order.
+fn decode_signed_channel(block: &[u8]) -> [u8; 16] {
+    // -128 is read as -127, both are -1.0.
+    let endpoint = |byte: u8| (byte as i8).max(-127) as i32;
+    channel_values(block, endpoint(block[0]), endpoint(block[1]), -127, 127).map(|value| value as i8 as u8)
+}
+
+/// Interpolates values of texels between the endpoints, blocks with six values also have the minimum and maximum.
+fn channel_values(block: &[u8], a: i32, b: i32, min: i32, max: i32) -> [i32; 16] {
+    let mut palette = [a, b, 0, 0, 0, 0, min, max];
+    if a > b {
+        for i in 1..7 {
+            palette[i + 1] = ((7 - i as i32) * a + i as i32 * b) / 7;
+        }
+    } else {
+        for i in 1..5 {
+            palette[i + 1] = ((5 - i as i32) * a + i as i32 * b) / 5;
+        }
+    }
+
+    let indices = block[2..8].iter().rev().fold(0u64, |bits, &byte| bits << 8 | byte as u64);
+    let mut values = [0; 16];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = palette[(indices >> (3 * i) & 0b111) as usize];
+    }
+    values
+}
+
+pub(crate) fn decode_bc1(block: &[u8]) -> [[u8; 4]; 16] {
+    decode_color(block, true)
+}
+
+pub(crate) fn decode_bc2(block: &[u8]) -> [[u8; 4]; 16] {
+    let mut texels = decode_color(&block[8..], false);
+    for (i, texel) in texels.iter_mut().enumerate() {
+        let alpha = block[i / 2] >> (4 * (i % 2)) & 0xf;
+        texel[3] = alpha * 17;
+    }
+    texels
+}
+
+pub(crate) fn decode_bc3(block: &[u8]) -> [[u8; 4]; 16] {
+    let mut texels = decode_color(&block[8..], false);
+    for (texel, alpha) in texels.iter_mut().zip(decode_channel(block)) {
+        texel[3] = alpha;
+    }
+    texels
+}
+
+pub(crate) fn decode_bc4(block: &[u8]) -> [[u8; 1]; 16] {
+    decode_channel(block).map(|value| [value])
+}
+
+pub(crate) fn decode_bc5(block: &[u8]) -> [[u8; 2]; 16] {
+    interleave(decode_channel(block), decode_channel(&block[8..]))
+}
+
+pub(crate) fn decode_bc4_snorm(block: &[u8]) -> [[u8; 1]; 16] {
+    decode_signed_channel(block).map(|value| [value])
+}
+
+pub(crate) fn decode_bc5_snorm(block: &[u8]) -> [[u8; 2]; 16] {
+    interleave(decode_signed_channel(block), decode_signed_channel(&block[8..]))
+}
+
+fn interleave(red: [u8; 16], green: [u8; 16]) -> [[u8; 2]; 16] {
+    let mut texels = [[0; 2]; 16];
+    for (i, texel) in texels.iter_mut().enumerate() {
+        *texel = [red[i], green[i]];
+    }
+    texels
+}
+
+/// Layout of a BC7 mode, see the BPTC specification.
+struct Bc7Mode {
+    subsets: usize,
+    partition_bits: u32,
+    rotation_bits: u32,
+    index_selection_bits: u32,
+    color_bits: u32,
+    alpha_bits: u32,
+    endpoint_p_bits: bool,
+    shared_p_bits: bool,
+    index_bits: u32,
+    index_bits2: u32,
+}
+
+const BC7_MODES: [Bc7Mode; 8] = [
+    Bc7Mode {
+        subsets: 3,
+        partition_bits: 4,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 4,
+        alpha_bits: 0,
+        endpoint_p_bits: true,
+        shared_p_bits: false,
+        index_bits: 3,
+        index_bits2: 0,
+    },
+    Bc7Mode {
+        subsets: 2,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 6,
+        alpha_bits: 0,
+        endpoint_p_bits: false,
+        shared_p_bits: true,
+        index_bits: 3,
+        index_bits2: 0,
+    },
+    Bc7Mode {
+        subsets: 3,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 5,
+        alpha_bits: 0,
+        endpoint_p_bits: false,
+        shared_p_bits: false,
+        index_bits: 2,
+        index_bits2: 0,
+    },
+    Bc7Mode {
+        subsets: 2,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 7,
+        alpha_bits: 0,
+        endpoint_p_bits: true,
+        shared_p_bits: false,
+        index_bits: 2,
+        index_bits2: 0,
+    },
+    Bc7Mode {
+        subsets: 1,
+        partition_bits: 0,
+        rotation_bits: 2,
+        index_selection_bits: 1,
+        color_bits: 5,
+        alpha_bits: 6,
+        endpoint_p_bits: false,
+        shared_p_bits: false,
+        index_bits: 2,
+        index_bits2: 3,
+    },
+    Bc7Mode {
+        subsets: 1,
+        partition_bits: 0,
+        rotation_bits: 2,
+        index_selection_bits: 0,
+        color_bits: 7,
+        alpha_bits: 8,
+        endpoint_p_bits: false,
+        shared_p_bits: false,
+        index_bits: 2,
+        index_bits2: 2,
+    },
+    Bc7Mode {
+        subsets: 1,
+        partition_bits: 0,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 7,
+        alpha_bits: 7,
+        endpoint_p_bits: true,
+        shared_p_bits: false,
+        index_bits: 4,
+        index_bits2: 0,
+    },
+    Bc7Mode {
+        subsets: 2,
+        partition_bits: 6,
+        rotation_bits: 0,
+        index_selection_bits: 0,
+        color_bits: 5,
+        alpha_bits: 5,
+        endpoint_p_bits: true,
+        shared_p_bits: false,
+        index_bits: 2,
+        index_bits2: 0,
+    },
+];
+
+const WEIGHTS2: [u32; 4] = [0, 21, 43, 64];
+const WEIGHTS3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+const WEIGHTS4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+/// Subsets of texels of two-subset partitions, a set bit is the second subset.
+const PARTITIONS2: [u16; 64] = [
+    0xcccc, 0x8888, 0xeeee, 0xecc8, 0xc880, 0xfeec, 0xfec8, 0xec80,
+    0xc800, 0xffec, 0xfe80, 0xe800, 0xffe8, 0xff00, 0xfff0, 0xf000,
+    0xf710, 0x008e, 0x7100, 0x08ce, 0x008c, 0x7310, 0x3100, 0x8cce,
+    0x088c, 0x3110, 0x6666, 0x366c, 0x17e8, 0x0ff0, 0x718e, 0x399c,
+    0xaaaa, 0xf0f0, 0x5a5a, 0x33cc, 0x3c3c, 0x55aa, 0x9696, 0xa55a,
+    0x73ce, 0x13c8, 0x324c, 0x3bdc, 0x6996, 0xc33c, 0x9966, 0x0660,
+    0x0272, 0x04e4, 0x4e40, 0x2720, 0xc936, 0x936c, 0x39c6, 0x639c,
+    0x9336, 0x9cc6, 0x817e, 0xe718, 0xccf0, 0x0fcc, 0x7744, 0xee22,
+];
+
+/// Subsets of texels of three-subset partitions.
+const PARTITIONS3: [[u8; 16]; 64] = [
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 1, 2, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 2, 0, 0, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 1, 0, 1, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1],
+    [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2],
+    [0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2, 0, 1, 1, 2],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0, 2, 2, 2, 0],
+    [0, 0, 0, 1, 0, 0, 1, 1, 0, 1, 1, 2, 1, 1, 2, 2],
+    [0, 1, 1, 1, 0, 0, 1, 1, 2, 0, 0, 1, 2, 2, 0, 0],
+    [0, 0, 0, 0, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 2, 2, 0, 0, 2, 2, 1, 1, 1, 1],
+    [0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2, 0, 2, 2, 2],
+    [0, 0, 0, 1, 0, 0, 0, 1, 2, 2, 2, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2],
+    [0, 0, 0, 0, 1, 1, 0, 0, 2, 2, 1, 0, 2, 2, 1, 0],
+    [0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1, 0, 0, 0, 0],
+    [0, 0, 1, 2, 0, 0, 1, 2, 1, 1, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1, 0, 1, 1, 0],
+    [0, 0, 0, 0, 0, 1, 1, 0, 1, 2, 2, 1, 1, 2, 2, 1],
+    [0, 0, 2, 2, 1, 1, 0, 2, 1, 1, 0, 2, 0, 0, 2, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 0, 0, 2, 2, 2, 2, 2],
+    [0, 0, 1, 1, 0, 1, 2, 2, 0, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 0, 0, 2, 0, 0, 0, 2, 2, 1, 1, 2, 2, 2, 1],
+    [0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 2, 2, 2],
+    [0, 2, 2, 2, 0, 0, 2, 2, 0, 0, 1, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 0, 0, 1, 2, 0, 0, 2, 2, 0, 2, 2, 2],
+    [0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0, 0, 1, 2, 0],
+    [0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0],
+    [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 1, 2, 0],
+    [0, 1, 2, 0, 2, 0, 1, 2, 1, 2, 0, 1, 0, 1, 2, 0],
+    [0, 0, 1, 1, 2, 2, 0, 0, 1, 1, 2, 2, 0, 0, 1, 1],
+    [0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 0, 0, 0, 0, 1, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 0, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2, 1, 1, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 2, 2, 0, 0, 1, 1],
+    [0, 2, 2, 0, 1, 2, 2, 1, 0, 2, 2, 0, 1, 2, 2, 1],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 0, 1, 0, 1],
+    [0, 0, 0, 0, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1],
+    [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 2, 2, 2, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 2, 2, 2, 0, 1, 1, 1],
+    [0, 0, 0, 2, 1, 1, 1, 2, 0, 0, 0, 2, 1, 1, 1, 2],
+    [0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 2, 2, 2, 0, 1, 1, 1, 0, 1, 1, 1, 0, 2, 2, 2],
+    [0, 0, 0, 2, 1, 1, 1, 2, 1, 1, 1, 2, 0, 0, 0, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2, 2, 1, 1, 2],
+    [0, 1, 1, 0, 0, 1, 1, 0, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 0, 2, 2, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 2, 2],
+    [0, 0, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2, 0, 0, 2, 2],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 1, 1, 2],
+    [0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1],
+    [0, 2, 2, 2, 1, 2, 2, 2, 0, 2, 2, 2, 1, 2, 2, 2],
+    [0, 1, 0, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2],
+    [0, 1, 1, 1, 2, 0, 1, 1, 2, 2, 0, 1, 2, 2, 2, 0],
+];
+
+/// Anchor texels of the second subset of two-subset partitions.
+const ANCHORS2: [usize; 64] = [
+    15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+    15, 2, 8, 2, 2, 8, 8, 15, 2, 8, 2, 2, 8, 8, 2, 2,
+    15, 15, 6, 8, 2, 8, 15, 15, 2, 8, 2, 2, 2, 15, 15, 6,
+    6, 2, 6, 8, 15, 15, 2, 2, 15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+/// Anchor texels of the second subset of three-subset partitions.
+const ANCHORS3_2: [usize; 64] = [
+    3, 3, 15, 15, 8, 3, 15, 15, 8, 8, 6, 6, 6, 5, 3, 3,
+    3, 3, 8, 15, 3, 3, 6, 10, 5, 8, 8, 6, 8, 5, 15, 15,
+    8, 15, 3, 5, 6, 10, 8, 15, 15, 3, 15, 5, 15, 15, 15, 15,
+    3, 15, 5, 5, 5, 8, 5, 10, 5, 10, 8, 13, 15, 12, 3, 3,
+];
+
+/// Anchor texels of the third subset of three-subset partitions.
+const ANCHORS3_3: [usize; 64] = [
+    15, 8, 8, 3, 15, 15, 3, 8, 15, 15, 15, 15, 15, 15, 15, 8,
+    15, 8, 15, 3, 15, 8, 15, 8, 3, 15, 6, 10, 15, 15, 10, 8,
+    15, 3, 15, 10, 10, 8, 9, 10, 6, 15, 8, 15, 3, 6, 6, 8,
+    15, 3, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 3, 15, 15, 8,
+];
+
+/// Reads bits of a block from the least significant one.
+struct Bits {
+    bits: u128,
+    position: u32,
+}
+
+impl Bits {
+    fn read(&mut self, count: u32) -> u32 {
+        let value = (self.bits >> self.position) as u32 & ((1 << count) - 1);
+        self.position += count;
+        value
+    }
+}
+
+pub(crate) fn decode_bc7(block: &[u8]) -> [[u8; 4]; 16] {
+    let mut bits = Bits {
+        bits: u128::from_le_bytes(block[..16].try_into().expect("blocks are 16 bytes")),
+        position: 0,
+    };
+    let mode_index = bits.bits.trailing_zeros();
+    // Blocks without a mode are reserved and decode to transparent black.
+    if mode_index >= 8 {
+        return [[0; 4]; 16];
+    }
+    let mode = &BC7_MODES[mode_index as usize];
+    bits.position = mode_index + 1;
+
+    let partition = bits.read(mode.partition_bits) as usize;
+    let rotation = bits.read(mode.rotation_bits);
+    let index_selection = bits.read(mode.index_selection_bits);
+
+    // Channels of endpoints are stored one after another, for every subset two endpoints.
+    let endpoint_count = mode.subsets * 2;
+    let mut endpoints = [[0u32; 4]; 6];
+    for channel in 0..4 {
+        let channel_bits = if channel < 3 { mode.color_bits } else { mode.alpha_bits };
+        for endpoint in endpoints.iter_mut().take(endpoint_count) {
+            endpoint[channel] = bits.read(channel_bits);
+        }
+    }
+
+    let mut p_bits = [0; 6];
+    if mode.endpoint_p_bits {
+        for p_bit in p_bits.iter_mut().take(endpoint_count) {
+            *p_bit = bits.read(1);
+        }
+    } else if mode.shared_p_bits {
+        for subset in 0..mode.subsets {
+            let p_bit = bits.read(1);
+            p_bits[subset * 2] = p_bit;
+            p_bits[subset * 2 + 1] = p_bit;
+        }
+    }
+
+    let has_p_bits = mode.endpoint_p_bits || mode.shared_p_bits;
+    for (endpoint, p_bit) in endpoints.iter_mut().zip(p_bits).take(endpoint_count) {
+        for (channel, value) in endpoint.iter_mut().enumerate() {
+            let channel_bits = if channel < 3 { mode.color_bits } else { mode.alpha_bits };
+            *value = match (channel_bits, has_p_bits) {
+                (0, _) => 255,
+                (_, true) => expand(*value << 1 | p_bit, channel_bits + 1),
+                (_, false) => expand(*value, channel_bits),
+            };
+        }
+    }
+
+    let subset_of = |texel: usize| match mode.subsets {
+        1 => 0,
+        2 => (PARTITIONS2[partition] >> texel & 1) as usize,
+        _ => PARTITIONS3[partition][texel] as usize,
+    };
+    let is_anchor = |texel: usize| {
+        texel == 0
+            || match mode.subsets {
+                1 => false,
+                2 => texel == ANCHORS2[partition],
+                _ => texel == ANCHORS3_2[partition] || texel == ANCHORS3_3[partition],
+            }
+    };
+
+    // Anchor texels have an index with the most significant bit left out, it is always zero.
+    let mut indices = [0; 16];
+    for (texel, index) in indices.iter_mut().enumerate() {
+        *index = bits.read(mode.index_bits - is_anchor(texel) as u32);
+    }
+    let mut indices2 = [0; 16];
+    if mode.index_bits2 > 0 {
+        for (texel, index) in indices2.iter_mut().enumerate() {
+            *index = bits.read(mode.index_bits2 - (texel == 0) as u32);
+        }
+    }
+
+    let mut texels = [[0; 4]; 16];
+    for (texel, color) in texels.iter_mut().enumerate() {
+        let subset = subset_of(texel);
+        let (a, b) = (endpoints[subset * 2], endpoints[subset * 2 + 1]);
+
+        let (color_weight, alpha_weight) = if mode.index_bits2 == 0 {
+            let weight = weight(indices[texel], mode.index_bits);
+            (weight, weight)
+        } else if index_selection == 0 {
+            (weight(indices[texel], mode.index_bits), weight(indices2[texel], mode.index_bits2))
+        } else {
+            (weight(indices2[texel], mode.index_bits2), weight(indices[texel], mode.index_bits))
+        };
+
+        for channel in 0..4 {
+            let weight = if channel < 3 { color_weight } else { alpha_weight };
+            color[channel] = ((a[channel] * (64 - weight) + b[channel] * weight + 32) >> 6) as u8;
+        }
+        match rotation {
+            1 => color.swap(0, 3),
+            2 => color.swap(1, 3),
+            3 => color.swap(2, 3),
+            _ => {}
+        }
+    }
+    texels
+}
+
+/// Expands a value with the number of bits to 8 bits by repeating its most significant bits.
+fn expand(value: u32, bits: u32) -> u32 {
+    let value = value << (8 - bits);
+    value | value >> bits
+}
+
+fn weight(index: u32, bits: u32) -> u32 {
+    match bits {
+        2 => WEIGHTS2[index as usize],
+        3 => WEIGHTS3[index as usize],
+        _ => WEIGHTS4[index as usize],
+    }
+}
+
+/// Layout of a BC6H mode, see the BPTC specification.
+struct Bc6hMode {
+    /// Value of the mode bits, two bits for the first two modes and five bits for the others.
+    value: u32,
+    regions: usize,
+    transformed: bool,
+    endpoint_bits: u32,
+    delta_bits: [u32; 3],
+    /// Endpoint channels the bits after the mode are read into, with the first and the last bit of a run.
+    header: &'static [(usize, u32, u32)],
+}
+
+// Endpoint channels in the header, the first two endpoints belong to the first region.
+const R0: usize = 0;
+const G0: usize = 1;
+const B0: usize = 2;
+const R1: usize = 3;
+const G1: usize = 4;
+const B1: usize = 5;
+const R2: usize = 6;
+const G2: usize = 7;
+const B2: usize = 8;
+const R3: usize = 9;
+const G3: usize = 10;
+const B3: usize = 11;
+
+#[rustfmt::skip]
+const BC6H_MODES: [Bc6hMode; 14] = [
+    Bc6hMode {
+        value: 0b00,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 10,
+        delta_bits: [5, 5, 5],
+        header: &[
+            (G2, 4, 4), (B2, 4, 4), (B3, 4, 4), (R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 4), (G3, 4, 4),
+            (G2, 0, 3), (G1, 0, 4), (B3, 0, 0), (G3, 0, 3), (B1, 0, 4), (B3, 1, 1), (B2, 0, 3), (R2, 0, 4),
+            (B3, 2, 2), (R3, 0, 4), (B3, 3, 3),
+        ],
+    },
+    Bc6hMode {
+        value: 0b01,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 7,
+        delta_bits: [6, 6, 6],
+        header: &[
+            (G2, 5, 5), (G3, 4, 4), (G3, 5, 5), (R0, 0, 6), (B3, 0, 0), (B3, 1, 1), (B2, 4, 4), (G0, 0, 6),
+            (B2, 5, 5), (B3, 2, 2), (G2, 4, 4), (B0, 0, 6), (B3, 3, 3), (B3, 5, 5), (B3, 4, 4), (R1, 0, 5),
+            (G2, 0, 3), (G1, 0, 5), (G3, 0, 3), (B1, 0, 5), (B2, 0, 3), (R2, 0, 5), (R3, 0, 5),
+        ],
+    },
+    Bc6hMode {
+        value: 0b00010,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 11,
+        delta_bits: [5, 4, 4],
+        header: &[
+            (R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 4), (R0, 10, 10), (G2, 0, 3), (G1, 0, 3), (G0, 10, 10),
+            (B3, 0, 0), (G3, 0, 3), (B1, 0, 3), (B0, 10, 10), (B3, 1, 1), (B2, 0, 3), (R2, 0, 4), (B3, 2, 2),
+            (R3, 0, 4), (B3, 3, 3),
+        ],
+    },
+    Bc6hMode {
+        value: 0b00110,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 11,
+        delta_bits: [4, 5, 4],
+        header: &[
+            (R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 3), (R0, 10, 10), (G3, 4, 4), (G2, 0, 3), (G1, 0, 4),
+            (G0, 10, 10), (G3, 0, 3), (B1, 0, 3), (B0, 10, 10), (B3, 1, 1), (B2, 0, 3), (R2, 0, 3), (B3, 0, 0),
+            (B3, 2, 2), (R3, 0, 3), (G2, 4, 4), (B3, 3, 3),
+        ],
+    },
+    Bc6hMode {
+        value: 0b01010,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 11,
+        delta_bits: [4, 4, 5],
+        header: &[
+            (R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 3), (R0, 10, 10), (B2, 4, 4), (G2, 0, 3), (G1, 0, 3),
+            (G0, 10, 10), (B3, 0, 0), (G3, 0, 3), (B1, 0, 4), (B0, 10, 10), (B2, 0, 3), (R2, 0, 3), (B3, 1, 1),
+            (B3, 2, 2), (R3, 0, 3), (B3, 4, 4), (B3, 3, 3),
+        ],
+    },
+    Bc6hMode {
+        value: 0b01110,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 9,
+        delta_bits: [5, 5, 5],
+        header: &[
+            (R0, 0, 8), (B2, 4, 4), (G0, 0, 8), (G2, 4, 4), (B0, 0, 8), (B3, 4, 4), (R1, 0, 4), (G3, 4, 4),
+            (G2, 0, 3), (G1, 0, 4), (B3, 0, 0), (G3, 0, 3), (B1, 0, 4), (B3, 1, 1), (B2, 0, 3), (R2, 0, 4),
+            (B3, 2, 2), (R3, 0, 4), (B3, 3, 3),
+        ],
+    },
+    Bc6hMode {
+        value: 0b10010,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 8,
+        delta_bits: [6, 5, 5],
+        header: &[
+            (R0, 0, 7), (G3, 4, 4), (B2, 4, 4), (G0, 0, 7), (B3, 2, 2), (G2, 4, 4), (B0, 0, 7), (B3, 3, 3),
+            (B3, 4, 4), (R1, 0, 5), (G2, 0, 3), (G1, 0, 4), (B3, 0, 0), (G3, 0, 3), (B1, 0, 4), (B3, 1, 1),
+            (B2, 0, 3), (R2, 0, 5), (R3, 0, 5),
+        ],
+    },
+    Bc6hMode {
+        value: 0b10110,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 8,
+        delta_bits: [5, 6, 5],
+        header: &[
+            (R0, 0, 7), (B3, 0, 0), (B2, 4, 4), (G0, 0, 7), (G2, 5, 5), (G2, 4, 4), (B0, 0, 7), (G3, 5, 5),
+            (B3, 4, 4), (R1, 0, 4), (G3, 4, 4), (G2, 0, 3), (G1, 0, 5), (G3, 0, 3), (B1, 0, 4), (B3, 1, 1),
+            (B2, 0, 3), (R2, 0, 4), (B3, 2, 2), (R3, 0, 4), (B3, 3, 3),
+        ],
+    },
+    Bc6hMode {
+        value: 0b11010,
+        regions: 2,
+        transformed: true,
+        endpoint_bits: 8,
+        delta_bits: [5, 5, 6],
+        header: &[
+            (R0, 0, 7), (B3, 1, 1), (B2, 4, 4), (G0, 0, 7), (B2, 5, 5), (G2, 4, 4), (B0, 0, 7), (B3, 5, 5),
+            (B3, 4, 4), (R1, 0, 4), (G3, 4, 4), (G2, 0, 3), (G1, 0, 4), (B3, 0, 0), (G3, 0, 3), (B1, 0, 5),
+            (B2, 0, 3), (R2, 0, 4), (B3, 2, 2), (R3, 0, 4), (B3, 3, 3),
+        ],
+    },
+    Bc6hMode {
+        value: 0b11110,
+        regions: 2,
+        transformed: false,
+        endpoint_bits: 6,
+        delta_bits: [6, 6, 6],
+        header: &[
+            (R0, 0, 5), (G3, 4, 4), (B3, 0, 0), (B3, 1, 1), (B2, 4, 4), (G0, 0, 5), (G2, 5, 5), (B2, 5, 5),
+            (B3, 2, 2), (G2, 4, 4), (B0, 0, 5), (G3, 5, 5), (B3, 3, 3), (B3, 5, 5), (B3, 4, 4), (R1, 0, 5),
+            (G2, 0, 3), (G1, 0, 5), (G3, 0, 3), (B1, 0, 5), (B2, 0, 3), (R2, 0, 5), (R3, 0, 5),
+        ],
+    },
+    Bc6hMode {
+        value: 0b00011,
+        regions: 1,
+        transformed: false,
+        endpoint_bits: 10,
+        delta_bits: [10, 10, 10],
+        header: &[(R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 9), (G1, 0, 9), (B1, 0, 9)],
+    },
+    Bc6hMode {
+        value: 0b00111,
+        regions: 1,
+        transformed: true,
+        endpoint_bits: 11,
+        delta_bits: [9, 9, 9],
+        header: &[
+            (R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 8), (R0, 10, 10), (G1, 0, 8), (G0, 10, 10), (B1, 0, 8),
+            (B0, 10, 10),
+        ],
+    },
+    Bc6hMode {
+        value: 0b01011,
+        regions: 1,
+        transformed: true,
+        endpoint_bits: 12,
+        delta_bits: [8, 8, 8],
+        header: &[
+            (R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 7), (R0, 11, 10), (G1, 0, 7), (G0, 11, 10), (B1, 0, 7),
+            (B0, 11, 10),
+        ],
+    },
+    Bc6hMode {
+        value: 0b01111,
+        regions: 1,
+        transformed: true,
+        endpoint_bits: 16,
+        delta_bits: [4, 4, 4],
+        header: &[
+            (R0, 0, 9), (G0, 0, 9), (B0, 0, 9), (R1, 0, 3), (R0, 15, 10), (G1, 0, 3), (G0, 15, 10), (B1, 0, 3),
+            (B0, 15, 10),
+        ],
+    },
+];
+
+/// Half float 1.0, the alpha of BC6H texels.
+const HALF_ONE: [u8; 2] = [0x00, 0x3c];
+
+pub(crate) fn decode_bc6h_ufloat(block: &[u8]) -> [[u8; 8]; 16] {
+    decode_bc6h(block, false)
+}
+
+pub(crate) fn decode_bc6h_sfloat(block: &[u8]) -> [[u8; 8]; 16] {
+    decode_bc6h(block, true)
+}
+
+/// Decodes a BC6H block into RGBA half float texels in row order.
+fn decode_bc6h(block: &[u8], signed: bool) -> [[u8; 8]; 16] {
+    let mut bits = Bits {
+        bits: u128::from_le_bytes(block[..16].try_into().expect("blocks are 16 bytes")),
+        position: 0,
+    };
+    let mode_value = match bits.read(2) {
+        value @ 0..=1 => value,
+        value => value | bits.read(3) << 2,
+    };
+    // Reserved modes decode to opaque black.
+    let mode = match BC6H_MODES.iter().find(|mode| mode.value == mode_value) {
+        Some(mode) => mode,
+        None => return [[0, 0, 0, 0, 0, 0, HALF_ONE[0], HALF_ONE[1]]; 16],
+    };
+
+    // Runs of bits from a higher first bit to a lower last bit are stored reversed.
+    let mut endpoints = [0i32; 12];
+    for &(channel, first, last) in mode.header {
+        for i in 0..=first.abs_diff(last) {
+            let bit = if first <= last { first + i } else { first - i };
+            endpoints[channel] |= (bits.read(1) << bit) as i32;
+        }
+    }
+    let partition = if mode.regions == 2 { bits.read(5) as usize } else { 0 };
+
+    // Transformed modes store the other endpoints as deltas to the first one.
+    let endpoint_count = mode.regions * 2;
+    let mask = (1 << mode.endpoint_bits) - 1;
+    for channel in 0..3 {
+        if signed {
+            endpoints[channel] = sign_extend(endpoints[channel], mode.endpoint_bits);
+        }
+        let base = endpoints[channel];
+        for endpoint in 1..endpoint_count {
+            let value = &mut endpoints[endpoint * 3 + channel];
+            if mode.transformed {
+                *value = (base + sign_extend(*value, mode.delta_bits[channel])) & mask;
+            }
+            if signed {
+                *value = sign_extend(*value, mode.endpoint_bits);
+            }
+        }
+    }
+    let endpoints = endpoints.map(|value| unquantize(value, mode.endpoint_bits, signed));
+
+    let index_bits = if mode.regions == 2 { 3 } else { 4 };
+    let mut texels = [[0; 8]; 16];
+    for (texel, color) in texels.iter_mut().enumerate() {
+        let (region, is_anchor) = match mode.regions {
+            1 => (0, texel == 0),
+            _ => (
+                (PARTITIONS2[partition] >> texel & 1) as usize,
+                texel == 0 || texel == ANCHORS2[partition],
+            ),
+        };
+        // Anchor texels have an index with the most significant bit left out, it is always zero.
+        let weight = weight(bits.read(index_bits - is_anchor as u32), index_bits) as i32;
+        for channel in 0..3 {
+            let (a, b) = (endpoints[region * 6 + channel], endpoints[region * 6 + 3 + channel]);
+            let value = (a * (64 - weight) + b * weight + 32) >> 6;
+            color[channel * 2..][..2].copy_from_slice(&half_bits(value, signed).to_le_bytes());
+        }
+        color[6..].copy_from_slice(&HALF_ONE);
+    }
+    texels
+}
+
+fn sign_extend(value: i32, bits: u32) -> i32 {
+    let shift = 32 - bits;
+    value << shift >> shift
+}
+
+/// Scales an endpoint with the number of bits to 16 bits, or to 15 bits and a sign.
+fn unquantize(value: i32, bits: u32, signed: bool) -> i32 {
+    if !signed {
+        match value {
+            _ if bits >= 15 => value,
+            0 => 0,
+            _ if value == (1 << bits) - 1 => 0xffff,
+            _ => ((value << 16) + 0x8000) >> bits,
+        }
+    } else {
+        let magnitude = match value.abs() {
+            _ if bits >= 16 => value.abs(),
+            0 => 0,
+            magnitude if magnitude >= (1 << (bits - 1)) - 1 => 0x7fff,
+            magnitude => ((magnitude << 15) + 0x4000) >> (bits - 1),
+        };
+        magnitude * value.signum()
+    }
+}
+
+/// Scales an interpolated value to the bits of a half float, the largest values are 65504.
+fn half_bits(value: i32, signed: bool) -> u16 {
+    if !signed {
+        ((value * 31) >> 6) as u16
+    } else if value < 0 {
+        0x8000 | ((-value * 31) >> 5) as u16
+    } else {
+        ((value * 31) >> 5) as u16
+    }
+}
@@ -1,11 +1,24 @@
 use crate::rendering::render_pipeline::*;
 use crate::rendering::*;
+use crate::{Error, Result};
+
+use std::sync::Arc;
 
 /// Struct to store multiple structs needed for rendering.
 pub struct Bundle {
-    surface: wgpu::Surface,
+    surface: Option<wgpu::Surface>,
+    window: Option<Arc<winit::window::Window>>,
+    offscreen: Option<Arc<Texture>>,
+    depth: Texture,
+    msaa: Option<Arc<Texture>>,
+    sample_count: u32,
+    samplers: SamplerCache,
+    blits: BlitCache,
+    adapter: Option<wgpu::Adapter>,
+    adapter_info: Option<wgpu::AdapterInfo>,
+    present_modes: Vec<wgpu::PresentMode>,
     device: wgpu::Device,
-    queue: wgpu::Queue,
+    queue: Arc<wgpu::Queue>,
     config: wgpu::SurfaceConfiguration,
     size: winit::dpi::PhysicalSize<u32>,
 }
@@ -15,8 +28,33 @@ impl Bundle {
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
     /// Returns its surface.
-    pub fn surface(&self) -> &wgpu::Surface {
-        &self.surface
+    /// Returns None if the Bundle is headless.
+    pub fn surface(&self) -> Option<&wgpu::Surface> {
+        self.surface.as_ref()
+    }
+
+    /// Returns true if the Bundle renders into an offscreen target instead of a window.
+    pub fn is_headless(&self) -> bool {
+        self.surface.is_none()
+    }
+
+    /// Returns its offscreen color target.
+    /// Window Bundles only have one if their surface can't be read back, frames render into it instead.
+    /// Returns None if the Bundle renders into a window directly.
+    pub fn offscreen_texture(&self) -> Option<&Texture> {
+        self.offscreen.as_deref()
+    }
+
+    /// Returns its depth target with DEPTH_FORMAT.
+    /// It always has the size of the surface, it is recreated on resize.
+    pub fn depth_texture(&self) -> &Texture {
+        &self.depth
+    }
+
+    /// Returns the window it renders into.
+    /// Returns None if the Bundle is headless or created by new_custom.
+    pub fn window(&self) -> Option<&Arc<winit::window::Window>> {
+        self.window.as_ref()
     }
 
     /// Returns its device.
@@ -39,6 +77,21 @@ impl Bundle {
         self.size
     }
 
+    /// Returns its sampler cache, shared by textures created with the Bundle.
+    pub fn sampler_cache(&self) -> &SamplerCache {
+        &self.samplers
+    }
+
+    /// Returns a sampler with the options from its sampler cache.
+    pub fn sampler(&self, options: &SamplerOptions) -> Arc<wgpu::Sampler> {
+        self.samplers.get(&self.device, options)
+    }
+
+    /// Returns its cache of blit pipelines, used to generate mip levels.
+    pub fn blit_cache(&self) -> &BlitCache {
+        &self.blits
+    }
+
     /// Create a new Bundle with custom properties.
     pub fn new_custom(
         surface: wgpu::Surface,
@@ -58,72 +111,578 @@ impl Bundle {
         surface.configure(&device, &config);
 
         let size = (config.width, config.height).into();
+        let depth = Self::create_depth_texture(&device, &config, 1);
 
         Self {
-            surface,
+            surface: Some(surface),
+            window: None,
+            offscreen: None,
+            depth,
+            msaa: None,
+            sample_count: 1,
+            samplers: SamplerCache::new(),
+            blits: BlitCache::new(),
+            adapter: None,
+            adapter_info: None,
+            present_modes: Vec::new(),
             device,
-            queue,
+            queue: Arc::new(queue),
             config,
             size,
         }
     }
 
-    /// Create a new Bundle.
-    pub fn new(window: &winit::window::Window) -> Self {
-        async_std::task::block_on(Self::async_new(window))
+    /// Create a new Bundle with the default BundleDescriptor.
+    /// It keeps the window alive and follows its size, so resize does not have to be called.
+    pub fn new(window: &Arc<winit::window::Window>) -> Result<Self> {
+        Self::with_descriptor(window, &BundleDescriptor::default())
+    }
+
+    /// Create a new Bundle for the primary window of a MainLoop with its vsync preference.
+    pub fn for_main_loop(main_loop: &crate::main_loop::MainLoop) -> Result<Self> {
+        Self::for_window(main_loop.window(), main_loop.vsync())
+    }
+
+    /// Create a new Bundle with the default BundleDescriptor and a vsync preference.
+    pub(crate) fn for_window(window: &Arc<winit::window::Window>, vsync: bool) -> Result<Self> {
+        let descriptor = BundleDescriptor {
+            present_mode: Self::vsync_present_mode(vsync),
+            ..Default::default()
+        };
+        Self::with_descriptor(window, &descriptor)
+    }
+
+    /// Create a new Bundle with a chosen adapter, features, surface format and present mode.
+    pub fn with_descriptor(window: &Arc<winit::window::Window>, descriptor: &BundleDescriptor) -> Result<Self> {
+        async_std::task::block_on(Self::async_with_descriptor(window, descriptor))
     }
 
-    async fn async_new(window: &winit::window::Window) -> Self {
+    async fn async_with_descriptor(
+        window: &Arc<winit::window::Window>,
+        descriptor: &BundleDescriptor,
+    ) -> Result<Self> {
         let size = window.inner_size();
 
-        let instance = wgpu::Instance::new(wgpu::Backends::all());
-        let surface = unsafe { instance.create_surface(window) };
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .unwrap();
+        let instance = wgpu::Instance::new(descriptor.backends);
+        // SAFETY: the Bundle holds the window, so it outlives the surface.
+        let surface = unsafe { instance.create_surface(window.as_ref()) };
+        let adapter = Self::request_adapter(&instance, descriptor, Some(&surface)).await?;
+        let (device, queue) = Self::request_device(&adapter, descriptor).await?;
+
+        let formats = surface.get_supported_formats(&adapter);
+        let format = formats
+            .iter()
+            .copied()
+            .find(|format| format.describe().srgb == descriptor.srgb)
+            .or_else(|| formats.first().copied())
+            .ok_or(Error::UnsupportedSurface)?;
+
+        let present_modes = surface.get_supported_modes(&adapter);
+        let copy_src = descriptor.readable_surface && Self::surface_supports_copy_src(adapter.get_info().backend);
+        let config = wgpu::SurfaceConfiguration {
+            usage: if copy_src {
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+            } else {
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+            },
+            format,
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode: Self::supported_present_mode(&present_modes, descriptor.present_mode),
+        };
+
+        let mut bundle = Self::async_new_custom(surface, device, queue, config).await;
+        if descriptor.readable_surface && !copy_src {
+            // Frames render into an offscreen target which can be read back and blit it into the surface.
+            bundle.offscreen = Some(Arc::new(Self::create_offscreen_texture(&bundle.device, &bundle.config)));
+        }
+        bundle.window = Some(window.clone());
+        bundle.adapter_info = Some(adapter.get_info());
+        bundle.adapter = Some(adapter);
+        bundle.present_modes = present_modes;
+        bundle.set_sample_count(descriptor.sample_count)?;
+        Ok(bundle)
+    }
+
+    /// Create a new headless Bundle which renders into an offscreen target.
+    /// Does not need a window, so it can be used for tests and tools.
+    /// A software adapter is used if there is no GPU.
+    pub fn headless(width: u32, height: u32) -> Result<Self> {
+        let descriptor = BundleDescriptor {
+            limits: wgpu::Limits::downlevel_defaults(),
+            ..Default::default()
+        };
+        match Self::headless_with_descriptor(width, height, &descriptor) {
+            Err(Error::NoAdapter) => Self::headless_with_descriptor(
+                width,
+                height,
+                &BundleDescriptor {
+                    force_fallback_adapter: true,
+                    ..descriptor
+                },
+            ),
+            result => result,
+        }
+    }
+
+    /// Create a new headless Bundle with a chosen adapter and features.
+    /// The present mode of the descriptor is ignored.
+    pub fn headless_with_descriptor(width: u32, height: u32, descriptor: &BundleDescriptor) -> Result<Self> {
+        async_std::task::block_on(Self::async_headless_with_descriptor(width, height, descriptor))
+    }
+
+    async fn async_headless_with_descriptor(
+        width: u32,
+        height: u32,
+        descriptor: &BundleDescriptor,
+    ) -> Result<Self> {
+        let instance = wgpu::Instance::new(descriptor.backends);
+        let adapter = Self::request_adapter(&instance, descriptor, None).await?;
+        let (device, queue) = Self::request_device(&adapter, descriptor).await?;
 
-        let (device, queue) = adapter
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: if descriptor.srgb {
+                Self::HEADLESS_FORMAT
+            } else {
+                wgpu::TextureFormat::Rgba8Unorm
+            },
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: descriptor.present_mode,
+        };
+
+        let offscreen = Arc::new(Self::create_offscreen_texture(&device, &config));
+        let depth = Self::create_depth_texture(&device, &config, 1);
+        let size = (config.width, config.height).into();
+
+        let mut bundle = Self {
+            surface: None,
+            window: None,
+            offscreen: Some(offscreen),
+            depth,
+            msaa: None,
+            sample_count: 1,
+            samplers: SamplerCache::new(),
+            blits: BlitCache::new(),
+            adapter_info: Some(adapter.get_info()),
+            adapter: Some(adapter),
+            present_modes: Vec::new(),
+            device,
+            queue: Arc::new(queue),
+            config,
+            size,
+        };
+        bundle.set_sample_count(descriptor.sample_count)?;
+        Ok(bundle)
+    }
+
+    /// Returns info of adapters of the backends, such as their names and types.
+    /// Indices of the list are used by BundleDescriptor::adapter.
+    pub fn adapters(backends: wgpu::Backends) -> Vec<wgpu::AdapterInfo> {
+        wgpu::Instance::new(backends)
+            .enumerate_adapters(backends)
+            .map(|adapter| adapter.get_info())
+            .collect()
+    }
+
+    async fn request_adapter(
+        instance: &wgpu::Instance,
+        descriptor: &BundleDescriptor,
+        surface: Option<&wgpu::Surface>,
+    ) -> Result<wgpu::Adapter> {
+        let adapter = match descriptor.adapter {
+            Some(index) => instance
+                .enumerate_adapters(descriptor.backends)
+                .nth(index)
+                .filter(|adapter| surface.map_or(true, |surface| adapter.is_surface_supported(surface))),
+            None => {
+                instance
+                    .request_adapter(&wgpu::RequestAdapterOptions {
+                        power_preference: descriptor.power_preference,
+                        compatible_surface: surface,
+                        force_fallback_adapter: descriptor.force_fallback_adapter,
+                    })
+                    .await
+            }
+        };
+
+        adapter.ok_or(Error::NoAdapter)
+    }
+
+    async fn request_device(
+        adapter: &wgpu::Adapter,
+        descriptor: &BundleDescriptor,
+    ) -> Result<(wgpu::Device, wgpu::Queue)> {
+        let missing = descriptor.required_features - adapter.features();
+        if !missing.is_empty() {
+            return Err(Error::MissingFeatures(missing));
+        }
+
+        let device = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty(),
-                    limits: if cfg!(target_arch = "wasm32") {
-                        wgpu::Limits::downlevel_webgl2_defaults()
-                    } else {
-                        wgpu::Limits::default()
-                    },
+                    features: descriptor.required_features | (descriptor.optional_features & adapter.features()),
+                    limits: descriptor.limits.clone(),
                     label: None,
                 },
                 None,
             )
-            .await
-            .unwrap();
+            .await?;
 
+        Ok(device)
+    }
+
+    /// Returns true if surfaces of the backend can be configured with `TextureUsages::COPY_SRC`.
+    /// wgpu 0.13 does not report supported usages, only DX12 surfaces always support it.
+    fn surface_supports_copy_src(backend: wgpu::Backend) -> bool {
+        backend == wgpu::Backend::Dx12
+    }
+
+    /// Returns the present mode if it is supported, Fifo otherwise.
+    /// Every mode is accepted if the supported modes are unknown.
+    fn supported_present_mode(present_modes: &[wgpu::PresentMode], present_mode: wgpu::PresentMode) -> wgpu::PresentMode {
+        if present_modes.is_empty() || present_modes.contains(&present_mode) {
+            present_mode
+        } else {
+            wgpu::PresentMode::Fifo
+        }
+    }
+
+    /// Returns info of its adapter.
+    /// Returns None if the Bundle is created by new_custom.
+    pub fn adapter_info(&self) -> Option<&wgpu::AdapterInfo> {
+        self.adapter_info.as_ref()
+    }
+
+    /// Returns features enabled on its device.
+    pub fn features(&self) -> wgpu::Features {
+        self.device.features()
+    }
+
+    /// The color format of headless Bundles.
+    pub const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// Returns its surface_texture.
+    /// Headless Bundles return their offscreen color target.
+    /// The surface is resized to the window and reconfigured if it has been lost or outdated.
+    /// Returns None if the frame should be skipped, such as when the window is minimized.
+    /// Returns Error::SurfaceOutOfMemory if the device ran out of memory, the Bundle can't render anymore.
+    pub fn surface_texture(&mut self) -> Result<Option<SurfaceTexture>> {
+        if self.surface.is_none() {
+            return Ok(self.offscreen.clone().map(SurfaceTexture::Offscreen));
+        }
+
+        if let Some(window) = &self.window {
+            let size = window.inner_size();
+            if size.width == 0 || size.height == 0 {
+                return Ok(None);
+            }
+            if size != self.size {
+                self.resize(size.width, size.height);
+            }
+        }
+
+        let surface = match &self.surface {
+            Some(surface) => surface,
+            None => return Ok(None),
+        };
+        let surface_texture = match surface.get_current_texture() {
+            Ok(surface_texture) => surface_texture,
+            Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                surface.configure(&self.device, &self.config);
+                match surface.get_current_texture() {
+                    Ok(surface_texture) => surface_texture,
+                    Err(wgpu::SurfaceError::OutOfMemory) => return Err(Error::SurfaceOutOfMemory),
+                    Err(_) => return Ok(None),
+                }
+            }
+            Err(wgpu::SurfaceError::Timeout) => {
+                log::warn!("timed out waiting for a surface texture, skipping a frame");
+                return Ok(None);
+            }
+            Err(wgpu::SurfaceError::OutOfMemory) => return Err(Error::SurfaceOutOfMemory),
+        };
+
+        match self.offscreen.clone() {
+            Some(target) => {
+                let view = surface_texture
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+                let mut encoder = self.encoder();
+                self.blits
+                    .get(&self.device, self.config.format)
+                    .blit(&self.device, &mut encoder, target.view(), &view);
+
+                Ok(Some(SurfaceTexture::Intermediate {
+                    target,
+                    surface_texture,
+                    blit: encoder.finish(),
+                    queue: self.queue.clone(),
+                }))
+            }
+            None => Ok(Some(SurfaceTexture::Surface(surface_texture))),
+        }
+    }
+
+    /// Begins a new frame with its surface texture, a view of it and a new encoder.
+    /// Returns None if the frame should be skipped and errors of acquiring the surface texture, see surface_texture.
+    pub fn begin_frame(&mut self) -> Result<Option<Frame>> {
+        let frame = self
+            .surface_texture()?
+            .map(|surface_texture| Frame::new(surface_texture, self.msaa.clone(), self.encoder(), self.queue.clone()));
+        Ok(frame)
+    }
+
+    /// Returns a view of a new surface texture.
+    /// Returns None if the frame should be skipped or the surface texture can't be acquired, see surface_texture.
+    #[deprecated(note = "use begin_frame, the Frame presents its surface texture on finish")]
+    pub fn surface_view(&mut self) -> Option<wgpu::TextureView> {
+        match self.begin_frame() {
+            Ok(frame) => frame.map(Frame::into_view),
+            Err(error) => {
+                log::error!("{}", error);
+                None
+            }
+        }
+    }
+
+    fn create_offscreen_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Texture {
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_supported_formats(&adapter)[0],
-            width: size.width,
-            height: size.height,
-            present_mode: wgpu::PresentMode::Fifo,
+            usage: config.usage | wgpu::TextureUsages::COPY_SRC,
+            ..config.clone()
         };
-        surface.configure(&device, &config);
+        Texture::render_target_from_device(device, &config, 1, "Offscreen Texture")
+    }
+
+    fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> Texture {
+        Texture::depth_texture_from_device(device, config, Self::DEPTH_FORMAT, sample_count, "Depth Texture")
+    }
+
+    /// Recreates the depth and multisampled targets after a change of size or sample count.
+    fn recreate_targets(&mut self) {
+        self.depth = Self::create_depth_texture(&self.device, &self.config, self.sample_count);
+        self.msaa = (self.sample_count > 1).then(|| {
+            Arc::new(Texture::render_target_from_device(
+                &self.device,
+                &self.config,
+                self.sample_count,
+                "Multisampled Texture",
+            ))
+        });
+    }
+
+    /// Returns its sample count, 1 means MSAA is off.
+    /// Pipelines created by the Bundle and its depth texture use it.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Sets its sample count, only 1 and 4 are supported.
+    /// MSAA also needs the adapter to support it for its surface format and DEPTH_FORMAT.
+    /// Recreates its depth and multisampled targets, pipelines and depth textures
+    /// created with the previous sample count have to be recreated too.
+    pub fn set_sample_count(&mut self, sample_count: u32) -> Result<()> {
+        let supported = sample_count == 1
+            || (sample_count == 4
+                && self.format_flags(self.config.format).contains(
+                    wgpu::TextureFormatFeatureFlags::MULTISAMPLE | wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE,
+                )
+                && self
+                    .format_flags(Self::DEPTH_FORMAT)
+                    .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE));
+        if !supported {
+            return Err(Error::UnsupportedSampleCount(sample_count));
+        }
+        if sample_count != self.sample_count {
+            self.sample_count = sample_count;
+            self.recreate_targets();
+        }
+        Ok(())
+    }
+
+    /// Returns feature flags of the format on its adapter.
+    /// Guaranteed flags are used for Bundles created by new_custom.
+    fn format_flags(&self, format: wgpu::TextureFormat) -> wgpu::TextureFormatFeatureFlags {
+        match &self.adapter {
+            Some(adapter) => adapter.get_texture_format_features(format).flags,
+            None => format.describe().guaranteed_format_features.flags,
+        }
+    }
+
+    /// Returns its multisampled color target, frames render into it and resolve it into the surface.
+    /// Returns None if MSAA is off.
+    pub fn msaa_texture(&self) -> Option<&Texture> {
+        self.msaa.as_deref()
+    }
+
+    /// Copies the texture of a frame into an image.
+    /// Has to be called before the surface texture is presented.
+    /// Window surfaces can only be read if the Bundle is created with BundleDescriptor::readable_surface,
+    /// Error::UnreadableSurface is returned otherwise.
+    pub fn read_surface_texture(&self, surface_texture: &SurfaceTexture) -> Result<image::RgbaImage> {
+        if let SurfaceTexture::Surface(_) = surface_texture {
+            if !self.config.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+                return Err(Error::UnreadableSurface);
+            }
+        }
+        self.read_texture(
+            surface_texture.texture(),
+            self.config.format,
+            (self.config.width, self.config.height),
+        )
+    }
+
+    /// Copies the texture of a frame into a PNG file.
+    /// Has to be called before the surface texture is presented.
+    pub fn save_screenshot(&self, surface_texture: &SurfaceTexture, path: &str) -> Result<()> {
+        let image = self.read_surface_texture(surface_texture)?;
+        image
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(Error::ImageEncode)
+    }
+
+    /// Copies a color texture into an image.
+    /// The texture has to be created with `TextureUsages::COPY_SRC`.
+    /// Supports RGBA, BGRA and R8 formats.
+    pub fn read_texture(
+        &self,
+        texture: &wgpu::Texture,
+        format: wgpu::TextureFormat,
+        dimensions: (u32, u32),
+    ) -> Result<image::RgbaImage> {
+        self.read_texture_level(texture, format, dimensions, 0)
+    }
+
+    /// Copies a mip level of a color texture into an image, dimensions are of the level.
+    pub fn read_texture_level(
+        &self,
+        texture: &wgpu::Texture,
+        format: wgpu::TextureFormat,
+        dimensions: (u32, u32),
+        mip_level: u32,
+    ) -> Result<image::RgbaImage> {
+        self.read_texture_layer_level(texture, format, dimensions, 0, mip_level)
+    }
+
+    /// Copies a mip level of a layer of a color texture array or cube into an image, dimensions are of the level.
+    pub fn read_texture_layer_level(
+        &self,
+        texture: &wgpu::Texture,
+        format: wgpu::TextureFormat,
+        dimensions: (u32, u32),
+        layer: u32,
+        mip_level: u32,
+    ) -> Result<image::RgbaImage> {
+        let bytes_per_pixel = match format {
+            wgpu::TextureFormat::Rgba8Unorm
+            | wgpu::TextureFormat::Rgba8UnormSrgb
+            | wgpu::TextureFormat::Bgra8Unorm
+            | wgpu::TextureFormat::Bgra8UnormSrgb => 4,
+            wgpu::TextureFormat::R8Unorm => 1,
+            _ => return Err(Error::UnsupportedFormat(format)),
+        };
+
+        let (width, height) = dimensions;
+        let unpadded_bytes_per_row = width * bytes_per_pixel;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
 
-        Self::async_new_custom(surface, device, queue, config).await
+        let mut encoder = self.encoder();
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                aspect: wgpu::TextureAspect::All,
+                texture,
+                mip_level,
+                origin: wgpu::Origin3d { x: 0, y: 0, z: layer },
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(padded_bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_bytes_per_row as usize) {
+                let row = &row[..unpadded_bytes_per_row as usize];
+                match format {
+                    wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => {
+                        for pixel in row.chunks(4) {
+                            pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+                        }
+                    }
+                    wgpu::TextureFormat::R8Unorm => {
+                        for &value in row {
+                            pixels.extend_from_slice(&[value, value, value, 255]);
+                        }
+                    }
+                    _ => pixels.extend_from_slice(row),
+                }
+            }
+        }
+        buffer.unmap();
+
+        Ok(image::RgbaImage::from_raw(width, height, pixels)
+            .expect("readback buffer matches the texture dimensions"))
+    }
+
+    /// Sets whether presenting waits for the vertical blank.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.set_present_mode(Self::vsync_present_mode(vsync));
     }
 
-    /// Returns its surface_texture. 
-    pub fn surface_texture(&self) -> wgpu::SurfaceTexture {
-        self.surface.get_current_texture().unwrap()
+    fn vsync_present_mode(vsync: bool) -> wgpu::PresentMode {
+        if vsync {
+            wgpu::PresentMode::Fifo
+        } else {
+            wgpu::PresentMode::Immediate
+        }
+    }
+
+    /// Returns its present mode.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.config.present_mode
+    }
+
+    /// Sets its present mode, Fifo is used if the surface does not support it.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        self.config.present_mode = Self::supported_present_mode(&self.present_modes, present_mode);
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.config);
+        }
     }
 
-    /// Returns its surface_view.
-    pub fn surface_view(&self) -> wgpu::TextureView {
-        self.surface_texture().texture
-        .create_view(&wgpu::TextureViewDescriptor::default())
+    /// Returns present modes supported by its surface.
+    /// Returns an empty slice if they are unknown.
+    pub fn present_modes(&self) -> &[wgpu::PresentMode] {
+        &self.present_modes
     }
 
     /// Creates a new encoder.
@@ -139,13 +698,20 @@ impl Bundle {
         self.config().format
     }
 
-    /// Resizes its surface.
-    /// Should be called when window has been resized.
+    /// Resizes its surface and depth target.
+    /// Bundles created by new follow their window, others have to be resized manually.
     pub fn resize(&mut self, width: u32, height: u32) {
         if width > 0 && height > 0 {
             self.config.width = width;
             self.config.height = height;
-            self.surface.configure(&self.device, &self.config);
+            self.size = (width, height).into();
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.config);
+            }
+            if self.offscreen.is_some() {
+                self.offscreen = Some(Arc::new(Self::create_offscreen_texture(&self.device, &self.config)));
+            }
+            self.recreate_targets();
         }
     }
 
@@ -154,7 +720,7 @@ impl Bundle {
         let texture_bind_group_layout = super::Texture::bind_group_layout(self);
         let mvp_bind_group_layout = super::Uniform::<glam::Mat4>::bind_group_layout(self);
 
-        let texture_pipeline = RenderPipeline::new(
+        let texture_pipeline = RenderPipeline::new_multisampled(
             &self.device,
             include_str!("../shaders/shader.wgsl"),
             &[&texture_bind_group_layout, &mvp_bind_group_layout],
@@ -167,6 +733,7 @@ impl Bundle {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
+            self.sample_count,
         );
 
         texture_pipeline
@@ -177,7 +744,7 @@ impl Bundle {
         let texture_bind_group_layout = super::Texture::bind_group_layout(self);
         let mvp_bind_group_layout = super::Uniform::<glam::Mat4>::bind_group_layout(self);
 
-        let texture_pipeline = RenderPipeline::new(
+        let texture_pipeline = RenderPipeline::new_multisampled(
             &self.device,
             include_str!("../shaders/instanced.wgsl"),
             &[&texture_bind_group_layout, &mvp_bind_group_layout],
@@ -193,6 +760,7 @@ impl Bundle {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
+            self.sample_count,
         );
 
         texture_pipeline
@@ -204,7 +772,7 @@ impl Bundle {
         let mvp_bind_group_layout = super::Uniform::<glam::Mat4>::bind_group_layout(self);
         let color_bind_group_layout = super::Uniform::<glam::Vec4>::bind_group_layout(self);
 
-        let text_pipeline = RenderPipeline::new(
+        let text_pipeline = RenderPipeline::new_multisampled(
             &self.device,
             include_str!("../shaders/text.wgsl"),
             &[
@@ -221,8 +789,104 @@ impl Bundle {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
+            self.sample_count,
         );
 
         text_pipeline
     }
 }
+
+
+/// Texture a frame is rendered to.
+/// Either a texture of a window surface or an offscreen target of a headless Bundle.
+pub enum SurfaceTexture {
+    Surface(wgpu::SurfaceTexture),
+    /// Shared with the Bundle, so the Bundle can still be used while the frame is rendered.
+    Offscreen(Arc<Texture>),
+    /// Offscreen target of a window surface which can't be read back.
+    /// The blit into the surface texture is submitted when it is presented.
+    Intermediate {
+        target: Arc<Texture>,
+        surface_texture: wgpu::SurfaceTexture,
+        blit: wgpu::CommandBuffer,
+        queue: Arc<wgpu::Queue>,
+    },
+}
+
+impl SurfaceTexture {
+    /// Returns its texture.
+    pub fn texture(&self) -> &wgpu::Texture {
+        match self {
+            Self::Surface(surface_texture) => &surface_texture.texture,
+            Self::Offscreen(texture) | Self::Intermediate { target: texture, .. } => texture.texture(),
+        }
+    }
+
+    /// Presents the texture to the window.
+    /// Does nothing for offscreen targets.
+    pub fn present(self) {
+        match self {
+            Self::Surface(surface_texture) => surface_texture.present(),
+            Self::Offscreen(_) => {}
+            Self::Intermediate {
+                surface_texture,
+                blit,
+                queue,
+                ..
+            } => {
+                queue.submit(std::iter::once(blit));
+                surface_texture.present();
+            }
+        }
+    }
+}
+
+/// Options of a new Bundle.
+#[derive(Clone, Debug)]
+pub struct BundleDescriptor {
+    /// Backends to search for adapters.
+    pub backends: wgpu::Backends,
+    /// Preference of an adapter if no adapter is chosen.
+    pub power_preference: wgpu::PowerPreference,
+    /// Index of an adapter in the list returned by Bundle::adapters.
+    pub adapter: Option<usize>,
+    /// Use a software adapter if no adapter is chosen.
+    pub force_fallback_adapter: bool,
+    /// Features the device has to support, the Bundle fails with Error::MissingFeatures otherwise.
+    pub required_features: wgpu::Features,
+    /// Features enabled only if the adapter supports them, see Bundle::features.
+    pub optional_features: wgpu::Features,
+    pub limits: wgpu::Limits,
+    /// Use an sRGB surface format if true, a linear one otherwise.
+    /// The other kind is used if the surface does not support the requested one.
+    pub srgb: bool,
+    pub present_mode: wgpu::PresentMode,
+    /// Allow frames of the window surface to be read back, see Bundle::read_surface_texture.
+    /// Where the surface can't be copied, frames render into an offscreen target blitted into it.
+    /// Headless Bundles can always be read back.
+    pub readable_surface: bool,
+    /// Sample count of MSAA, see Bundle::set_sample_count.
+    pub sample_count: u32,
+}
+
+impl Default for BundleDescriptor {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            power_preference: wgpu::PowerPreference::default(),
+            adapter: None,
+            force_fallback_adapter: false,
+            required_features: wgpu::Features::empty(),
+            optional_features: wgpu::Features::empty(),
+            limits: if cfg!(target_arch = "wasm32") {
+                wgpu::Limits::downlevel_webgl2_defaults()
+            } else {
+                wgpu::Limits::default()
+            },
+            srgb: true,
+            present_mode: wgpu::PresentMode::Fifo,
+            readable_surface: false,
+            sample_count: 1,
+        }
+    }
+}
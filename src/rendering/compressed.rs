@@ -0,0 +1,390 @@
+use crate::{Error, Result};
+
+use super::{bcn, Mipmaps};
+
+const KTX2_MAGIC: [u8; 12] = [0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a];
+const DDS_MAGIC: [u8; 4] = *b"DDS ";
+
+/// Mip levels of a 2D texture read from a KTX2 or DDS container, usually block compressed.
+/// Cube faces are layers in the order of CubeFace::ALL.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureContainer {
+    pub format: wgpu::TextureFormat,
+    /// Dimensions of the base level.
+    pub dimensions: (u32, u32),
+    pub layer_count: u32,
+    pub view_dimension: wgpu::TextureViewDimension,
+    /// Bytes of every level from the base, each has its layers one after another.
+    pub levels: Vec<Vec<u8>>,
+}
+
+impl TextureContainer {
+    /// Reads a KTX2 or DDS container, recognized by its magic number.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.starts_with(&KTX2_MAGIC) {
+            Self::from_ktx2(bytes)
+        } else if bytes.starts_with(&DDS_MAGIC) {
+            Self::from_dds(bytes)
+        } else {
+            Err(Error::UnsupportedContainer("unknown magic number"))
+        }
+    }
+
+    /// Reads a KTX2 container. Supercompressed and volume textures are not supported.
+    pub fn from_ktx2(bytes: &[u8]) -> Result<Self> {
+        let reader = ktx2::Reader::new(bytes).map_err(Error::Ktx2Parse)?;
+        let header = reader.header();
+        if header.supercompression_scheme.is_some() {
+            return Err(Error::UnsupportedContainer("supercompression is not supported"));
+        }
+        if header.pixel_depth > 1 {
+            return Err(Error::UnsupportedContainer("volume textures are not supported"));
+        }
+        let format = header
+            .format
+            .and_then(ktx2_format)
+            .ok_or(Error::UnsupportedContainer("texture format is not supported"))?;
+
+        // The reader only checks the last level is in bounds, levels are sliced without checks.
+        let level_count = header.level_count.max(1) as usize;
+        for level in 0..level_count {
+            let index = &bytes[80 + level * 24..][..16];
+            let offset = u64::from_le_bytes(index[..8].try_into().unwrap());
+            let length = u64::from_le_bytes(index[8..].try_into().unwrap());
+            if offset.checked_add(length).map_or(true, |end| end > bytes.len() as u64) {
+                return Err(Error::UnsupportedContainer("level data is out of bounds"));
+            }
+        }
+
+        let cube = header.face_count == 6;
+        let container = Self {
+            format,
+            dimensions: (header.pixel_width, header.pixel_height.max(1)),
+            layer_count: header.layer_count.max(1) * header.face_count,
+            view_dimension: match (cube, header.layer_count > 0) {
+                (true, true) => wgpu::TextureViewDimension::CubeArray,
+                (true, false) => wgpu::TextureViewDimension::Cube,
+                (false, true) => wgpu::TextureViewDimension::D2Array,
+                (false, false) => wgpu::TextureViewDimension::D2,
+            },
+            levels: reader.levels().map(<[u8]>::to_vec).collect(),
+        };
+        container.validate()
+    }
+
+    /// Reads a DDS container with a DX10 header or a DXT, ATI or BC FourCC.
+    /// Volume textures are not supported.
+    pub fn from_dds(bytes: &[u8]) -> Result<Self> {
+        let dds = ddsfile::Dds::read(bytes).map_err(Error::DdsParse)?;
+        if dds.header.depth.unwrap_or(1) > 1
+            || dds.header10.as_ref().is_some_and(|header10| {
+                matches!(header10.resource_dimension, ddsfile::D3D10ResourceDimension::Texture3D)
+            })
+        {
+            return Err(Error::UnsupportedContainer("volume textures are not supported"));
+        }
+
+        let format = match &dds.header10 {
+            Some(header10) => dxgi_format(header10.dxgi_format),
+            None => d3d_format(&dds),
+        }
+        .ok_or(Error::UnsupportedContainer("texture format is not supported"))?;
+
+        let (cube, array_size) = match &dds.header10 {
+            Some(header10) => (
+                header10.misc_flag.contains(ddsfile::MiscFlag::TEXTURECUBE),
+                header10.array_size.max(1),
+            ),
+            None => (dds.header.caps2.contains(ddsfile::Caps2::CUBEMAP), 1),
+        };
+        let layer_count = if cube { array_size * 6 } else { array_size };
+        let dimensions = (dds.header.width, dds.header.height);
+        let level_count = dds.header.mip_map_count.unwrap_or(1).clamp(1, Mipmaps::level_count(dimensions));
+
+        // DDS stores every level of a layer before the next layer.
+        let mut levels = vec![Vec::new(); level_count as usize];
+        let mut data = dds.data.as_slice();
+        for _ in 0..layer_count {
+            for (level, bytes) in levels.iter_mut().enumerate() {
+                let size = layer_size(format, Mipmaps::level_dimensions(dimensions, level as u32));
+                if data.len() < size {
+                    return Err(Error::UnsupportedContainer("level data is too short"));
+                }
+                let (layer, rest) = data.split_at(size);
+                bytes.extend_from_slice(layer);
+                data = rest;
+            }
+        }
+
+        let container = Self {
+            format,
+            dimensions,
+            layer_count,
+            view_dimension: match (cube, array_size > 1) {
+                (true, true) => wgpu::TextureViewDimension::CubeArray,
+                (true, false) => wgpu::TextureViewDimension::Cube,
+                (false, true) => wgpu::TextureViewDimension::D2Array,
+                (false, false) => wgpu::TextureViewDimension::D2,
+            },
+            levels,
+        };
+        container.validate()
+    }
+
+    /// Returns the number of mip levels.
+    pub fn level_count(&self) -> u32 {
+        self.levels.len() as u32
+    }
+
+    /// Returns dimensions of a mip level.
+    pub fn level_dimensions(&self, level: u32) -> (u32, u32) {
+        Mipmaps::level_dimensions(self.dimensions, level)
+    }
+
+    /// Returns bytes of a layer of a mip level.
+    pub fn layer(&self, level: u32, layer: u32) -> &[u8] {
+        let size = layer_size(self.format, self.level_dimensions(level));
+        &self.levels[level as usize][layer as usize * size..][..size]
+    }
+
+    /// Returns true if the device needs no features to sample its format
+    /// and the base level is made of whole blocks.
+    pub fn is_supported(&self, features: wgpu::Features) -> bool {
+        let info = self.format.describe();
+        let (block_width, block_height) = info.block_dimensions;
+        features.contains(info.required_features)
+            && self.dimensions.0 % block_width as u32 == 0
+            && self.dimensions.1 % block_height as u32 == 0
+    }
+
+    /// Decompresses BC1, BC2, BC3 and BC7 to Rgba8, BC4 to R8, BC5 to Rg8 and BC6H to Rgba16Float formats.
+    /// Other compressed formats return Error::UnsupportedFormat, uncompressed ones are cloned.
+    pub fn decompress(&self) -> Result<Self> {
+        use wgpu::TextureFormat as F;
+
+        let format = match self.format {
+            format if !format.describe().is_compressed() => return Ok(self.clone()),
+            F::Bc1RgbaUnorm | F::Bc2RgbaUnorm | F::Bc3RgbaUnorm | F::Bc7RgbaUnorm => F::Rgba8Unorm,
+            F::Bc1RgbaUnormSrgb | F::Bc2RgbaUnormSrgb | F::Bc3RgbaUnormSrgb | F::Bc7RgbaUnormSrgb => {
+                F::Rgba8UnormSrgb
+            }
+            F::Bc4RUnorm => F::R8Unorm,
+            F::Bc4RSnorm => F::R8Snorm,
+            F::Bc5RgUnorm => F::Rg8Unorm,
+            F::Bc5RgSnorm => F::Rg8Snorm,
+            F::Bc6hRgbUfloat | F::Bc6hRgbSfloat => F::Rgba16Float,
+            format => return Err(Error::UnsupportedFormat(format)),
+        };
+
+        let levels = (0..self.level_count())
+            .map(|level| {
+                let dimensions = self.level_dimensions(level);
+                let mut bytes = Vec::with_capacity(layer_size(format, dimensions) * self.layer_count as usize);
+                for layer in 0..self.layer_count {
+                    let blocks = self.layer(level, layer);
+                    match self.format {
+                        F::Bc1RgbaUnorm | F::Bc1RgbaUnormSrgb => decode_blocks(blocks, dimensions, bcn::decode_bc1, &mut bytes),
+                        F::Bc2RgbaUnorm | F::Bc2RgbaUnormSrgb => decode_blocks(blocks, dimensions, bcn::decode_bc2, &mut bytes),
+                        F::Bc3RgbaUnorm | F::Bc3RgbaUnormSrgb => decode_blocks(blocks, dimensions, bcn::decode_bc3, &mut bytes),
+                        F::Bc7RgbaUnorm | F::Bc7RgbaUnormSrgb => decode_blocks(blocks, dimensions, bcn::decode_bc7, &mut bytes),
+                        F::Bc4RUnorm => decode_blocks(blocks, dimensions, bcn::decode_bc4, &mut bytes),
+                        F::Bc4RSnorm => decode_blocks(blocks, dimensions, bcn::decode_bc4_snorm, &mut bytes),
+                        F::Bc5RgUnorm => decode_blocks(blocks, dimensions, bcn::decode_bc5, &mut bytes),
+                        F::Bc5RgSnorm => decode_blocks(blocks, dimensions, bcn::decode_bc5_snorm, &mut bytes),
+                        F::Bc6hRgbUfloat => decode_blocks(blocks, dimensions, bcn::decode_bc6h_ufloat, &mut bytes),
+                        _ => decode_blocks(blocks, dimensions, bcn::decode_bc6h_sfloat, &mut bytes),
+                    }
+                }
+                bytes
+            })
+            .collect();
+
+        Ok(Self {
+            format,
+            levels,
+            ..self.clone()
+        })
+    }
+
+    fn validate(self) -> Result<Self> {
+        if self.dimensions.0 == 0 || self.dimensions.1 == 0 || self.levels.is_empty() {
+            return Err(Error::UnsupportedContainer("texture is empty"));
+        }
+        if self.levels.len() as u32 > Mipmaps::level_count(self.dimensions) {
+            return Err(Error::UnsupportedContainer("too many mip levels"));
+        }
+        for (level, bytes) in self.levels.iter().enumerate() {
+            let size = layer_size(self.format, self.level_dimensions(level as u32));
+            if bytes.len() < size * self.layer_count as usize {
+                return Err(Error::UnsupportedContainer("level data is too short"));
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Returns the number of bytes of an image of the format, rounded up to whole blocks.
+pub(crate) fn layer_size(format: wgpu::TextureFormat, dimensions: (u32, u32)) -> usize {
+    let (blocks_x, blocks_y) = block_count(format, dimensions);
+    (blocks_x * blocks_y) as usize * format.describe().block_size as usize
+}
+
+/// Returns the number of blocks in a row and in a column of an image of the format.
+pub(crate) fn block_count(format: wgpu::TextureFormat, dimensions: (u32, u32)) -> (u32, u32) {
+    let (block_width, block_height) = format.describe().block_dimensions;
+    (
+        dimensions.0.div_ceil(block_width as u32),
+        dimensions.1.div_ceil(block_height as u32),
+    )
+}
+
+/// Decodes 4x4 blocks of an image into texels and appends those inside the dimensions in row order.
+fn decode_blocks<const N: usize>(
+    blocks: &[u8],
+    dimensions: (u32, u32),
+    decode: fn(&[u8]) -> [[u8; N]; 16],
+    bytes: &mut Vec<u8>,
+) {
+    let (width, height) = (dimensions.0 as usize, dimensions.1 as usize);
+    let start = bytes.len();
+    bytes.resize(start + width * height * N, 0);
+    let image = &mut bytes[start..];
+
+    let blocks_x = width.div_ceil(4);
+    let block_size = blocks.len() / (blocks_x * height.div_ceil(4));
+    for (i, block) in blocks.chunks_exact(block_size).enumerate() {
+        let (block_x, block_y) = (i % blocks_x * 4, i / blocks_x * 4);
+        for (texel, value) in decode(block).iter().enumerate() {
+            let (x, y) = (block_x + texel % 4, block_y + texel / 4);
+            if x < width && y < height {
+                image[(y * width + x) * N..][..N].copy_from_slice(value);
+            }
+        }
+    }
+}
+
+fn ktx2_format(format: ktx2::Format) -> Option<wgpu::TextureFormat> {
+    use ktx2::Format as K;
+    use wgpu::TextureFormat as F;
+
+    const ASTC_BLOCKS: [wgpu::AstcBlock; 14] = [
+        wgpu::AstcBlock::B4x4,
+        wgpu::AstcBlock::B5x4,
+        wgpu::AstcBlock::B5x5,
+        wgpu::AstcBlock::B6x5,
+        wgpu::AstcBlock::B6x6,
+        wgpu::AstcBlock::B8x5,
+        wgpu::AstcBlock::B8x6,
+        wgpu::AstcBlock::B8x8,
+        wgpu::AstcBlock::B10x5,
+        wgpu::AstcBlock::B10x6,
+        wgpu::AstcBlock::B10x8,
+        wgpu::AstcBlock::B10x10,
+        wgpu::AstcBlock::B12x10,
+        wgpu::AstcBlock::B12x12,
+    ];
+    // ASTC formats come in pairs of UNORM and SRGB, in the order of ASTC_BLOCKS.
+    let astc = format.0.get().wrapping_sub(K::ASTC_4x4_UNORM_BLOCK.0.get()) as usize;
+    if astc < ASTC_BLOCKS.len() * 2 {
+        return Some(F::Astc {
+            block: ASTC_BLOCKS[astc / 2],
+            channel: if astc % 2 == 0 {
+                wgpu::AstcChannel::Unorm
+            } else {
+                wgpu::AstcChannel::UnormSrgb
+            },
+        });
+    }
+
+    Some(match format {
+        K::R8_UNORM => F::R8Unorm,
+        K::R8G8_UNORM => F::Rg8Unorm,
+        K::R8G8B8A8_UNORM => F::Rgba8Unorm,
+        K::R8G8B8A8_SRGB => F::Rgba8UnormSrgb,
+        K::B8G8R8A8_UNORM => F::Bgra8Unorm,
+        K::B8G8R8A8_SRGB => F::Bgra8UnormSrgb,
+        K::R16G16B16A16_SFLOAT => F::Rgba16Float,
+        K::R32G32B32A32_SFLOAT => F::Rgba32Float,
+        // BC1 without alpha is sampled with an opaque alpha, the decoder is the same.
+        K::BC1_RGB_UNORM_BLOCK | K::BC1_RGBA_UNORM_BLOCK => F::Bc1RgbaUnorm,
+        K::BC1_RGB_SRGB_BLOCK | K::BC1_RGBA_SRGB_BLOCK => F::Bc1RgbaUnormSrgb,
+        K::BC2_UNORM_BLOCK => F::Bc2RgbaUnorm,
+        K::BC2_SRGB_BLOCK => F::Bc2RgbaUnormSrgb,
+        K::BC3_UNORM_BLOCK => F::Bc3RgbaUnorm,
+        K::BC3_SRGB_BLOCK => F::Bc3RgbaUnormSrgb,
+        K::BC4_UNORM_BLOCK => F::Bc4RUnorm,
+        K::BC4_SNORM_BLOCK => F::Bc4RSnorm,
+        K::BC5_UNORM_BLOCK => F::Bc5RgUnorm,
+        K::BC5_SNORM_BLOCK => F::Bc5RgSnorm,
+        K::BC6H_UFLOAT_BLOCK => F::Bc6hRgbUfloat,
+        K::BC6H_SFLOAT_BLOCK => F::Bc6hRgbSfloat,
+        K::BC7_UNORM_BLOCK => F::Bc7RgbaUnorm,
+        K::BC7_SRGB_BLOCK => F::Bc7RgbaUnormSrgb,
+        K::ETC2_R8G8B8_UNORM_BLOCK => F::Etc2Rgb8Unorm,
+        K::ETC2_R8G8B8_SRGB_BLOCK => F::Etc2Rgb8UnormSrgb,
+        K::ETC2_R8G8B8A1_UNORM_BLOCK => F::Etc2Rgb8A1Unorm,
+        K::ETC2_R8G8B8A1_SRGB_BLOCK => F::Etc2Rgb8A1UnormSrgb,
+        K::ETC2_R8G8B8A8_UNORM_BLOCK => F::Etc2Rgba8Unorm,
+        K::ETC2_R8G8B8A8_SRGB_BLOCK => F::Etc2Rgba8UnormSrgb,
+        K::EAC_R11_UNORM_BLOCK => F::EacR11Unorm,
+        K::EAC_R11_SNORM_BLOCK => F::EacR11Snorm,
+        K::EAC_R11G11_UNORM_BLOCK => F::EacRg11Unorm,
+        K::EAC_R11G11_SNORM_BLOCK => F::EacRg11Snorm,
+        _ => return None,
+    })
+}
+
+fn dxgi_format(format: ddsfile::DxgiFormat) -> Option<wgpu::TextureFormat> {
+    use ddsfile::DxgiFormat as D;
+    use wgpu::TextureFormat as F;
+
+    Some(match format {
+        D::R8_UNorm => F::R8Unorm,
+        D::R8G8_UNorm => F::Rg8Unorm,
+        D::R8G8B8A8_UNorm => F::Rgba8Unorm,
+        D::R8G8B8A8_UNorm_sRGB => F::Rgba8UnormSrgb,
+        D::B8G8R8A8_UNorm => F::Bgra8Unorm,
+        D::B8G8R8A8_UNorm_sRGB => F::Bgra8UnormSrgb,
+        D::R16G16B16A16_Float => F::Rgba16Float,
+        D::R32G32B32A32_Float => F::Rgba32Float,
+        D::BC1_UNorm => F::Bc1RgbaUnorm,
+        D::BC1_UNorm_sRGB => F::Bc1RgbaUnormSrgb,
+        D::BC2_UNorm => F::Bc2RgbaUnorm,
+        D::BC2_UNorm_sRGB => F::Bc2RgbaUnormSrgb,
+        D::BC3_UNorm => F::Bc3RgbaUnorm,
+        D::BC3_UNorm_sRGB => F::Bc3RgbaUnormSrgb,
+        D::BC4_UNorm => F::Bc4RUnorm,
+        D::BC4_SNorm => F::Bc4RSnorm,
+        D::BC5_UNorm => F::Bc5RgUnorm,
+        D::BC5_SNorm => F::Bc5RgSnorm,
+        D::BC6H_UF16 => F::Bc6hRgbUfloat,
+        D::BC6H_SF16 => F::Bc6hRgbSfloat,
+        D::BC7_UNorm => F::Bc7RgbaUnorm,
+        D::BC7_UNorm_sRGB => F::Bc7RgbaUnormSrgb,
+        _ => return None,
+    })
+}
+
+fn d3d_format(dds: &ddsfile::Dds) -> Option<wgpu::TextureFormat> {
+    use ddsfile::FourCC;
+    use wgpu::TextureFormat as F;
+
+    if let Some(FourCC(fourcc)) = dds.header.spf.fourcc {
+        return match fourcc {
+            FourCC::DXT1 => Some(F::Bc1RgbaUnorm),
+            // DXT2 and DXT4 have premultiplied alpha.
+            FourCC::DXT2 | FourCC::DXT3 => Some(F::Bc2RgbaUnorm),
+            FourCC::DXT4 | FourCC::DXT5 => Some(F::Bc3RgbaUnorm),
+            FourCC::ATI1 | FourCC::BC4_UNORM => Some(F::Bc4RUnorm),
+            FourCC::BC4_SNORM => Some(F::Bc4RSnorm),
+            FourCC::ATI2 => Some(F::Bc5RgUnorm),
+            FourCC::BC5_SNORM => Some(F::Bc5RgSnorm),
+            _ => None,
+        };
+    }
+    match dds.get_d3d_format()? {
+        ddsfile::D3DFormat::A8B8G8R8 => Some(F::Rgba8Unorm),
+        ddsfile::D3DFormat::A8R8G8B8 => Some(F::Bgra8Unorm),
+        _ => None,
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Error, Result};
+
+/// A face of a cube texture, the discriminant is its array layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CubeFace {
+    PositiveX = 0,
+    NegativeX = 1,
+    PositiveY = 2,
+    NegativeY = 3,
+    PositiveZ = 4,
+    NegativeZ = 5,
+}
+
+impl CubeFace {
+    /// Faces in the order of array layers.
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    /// Returns the direction sampled at a point of the face.
+    /// u and v are in -1..1, from the left and the top of the face.
+    pub fn direction(&self, u: f32, v: f32) -> glam::Vec3 {
+        let direction = match self {
+            CubeFace::PositiveX => glam::vec3(1.0, -v, -u),
+            CubeFace::NegativeX => glam::vec3(-1.0, -v, u),
+            CubeFace::PositiveY => glam::vec3(u, 1.0, v),
+            CubeFace::NegativeY => glam::vec3(u, -1.0, -v),
+            CubeFace::PositiveZ => glam::vec3(u, -v, 1.0),
+            CubeFace::NegativeZ => glam::vec3(-u, -v, -1.0),
+        };
+        direction.normalize()
+    }
+}
+
+/// Projects an equirectangular panorama onto six faces of a cube, in the order of CubeFace::ALL.
+/// The center of the panorama is in the -Z direction and its top is +Y.
+/// Returns Error::InvalidDimensions if the panorama or the faces are empty.
+pub fn equirectangular_to_cube_faces(image: &image::RgbaImage, face_size: u32) -> Result<Vec<image::RgbaImage>> {
+    if image.width() == 0 || image.height() == 0 {
+        return Err(Error::InvalidDimensions("panorama is empty"));
+    }
+    if face_size == 0 {
+        return Err(Error::InvalidDimensions("cube faces are empty"));
+    }
+
+    let faces = CubeFace::ALL
+        .iter()
+        .map(|face| {
+            image::RgbaImage::from_fn(face_size, face_size, |x, y| {
+                let u = (x as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                let v = (y as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                let direction = face.direction(u, v);
+
+                let longitude = direction.x.atan2(-direction.z);
+                let latitude = direction.y.clamp(-1.0, 1.0).asin();
+                sample_bilinear(
+                    image,
+                    (longitude / std::f32::consts::TAU + 0.5) * image.width() as f32,
+                    (0.5 - latitude / std::f32::consts::PI) * image.height() as f32,
+                )
+            })
+        })
+        .collect();
+    Ok(faces)
+}
+
+/// Samples between texel centers, wraps horizontally and clamps vertically.
+fn sample_bilinear(image: &image::RgbaImage, x: f32, y: f32) -> image::Rgba<u8> {
+    let (width, height) = (image.width() as i64, image.height() as i64);
+    let x = x - 0.5;
+    let y = y - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (x - x0, y - y0);
+
+    let texel = |x: i64, y: i64| {
+        let pixel = image.get_pixel(x.rem_euclid(width) as u32, y.clamp(0, height - 1) as u32);
+        glam::Vec4::from(pixel.0.map(f32::from))
+    };
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let top = texel(x0, y0).lerp(texel(x0 + 1, y0), tx);
+    let bottom = texel(x0, y0 + 1).lerp(texel(x0 + 1, y0 + 1), tx);
+    let color = top.lerp(bottom, ty);
+
+    image::Rgba(color.to_array().map(|channel| channel.round() as u8))
+}
@@ -1,4 +1,4 @@
-use anyhow::*;
+use crate::{Error, Result};
 
 use super::texture::Texture;
 
@@ -8,30 +8,41 @@ pub struct Font {
 
 impl Font {
     pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
-        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()).unwrap();
+        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .map_err(Error::FontParse)?;
         Ok(Self { font })
     }
 
     pub fn from_path(path: &str) -> Result<Self> {
-        let bytes: Vec<u8> = std::fs::read(path).unwrap();
+        let bytes: Vec<u8> = std::fs::read(path).map_err(|e| Error::missing_file(path, e))?;
         Self::from_bytes(bytes)
     }
 
+    pub fn metrics(&self, character: char, size: f32) -> fontdue::Metrics {
+        self.font.metrics(character, size)
+    }
+
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.font
+            .horizontal_line_metrics(size)
+            .map(|metrics| metrics.new_line_size)
+            .unwrap_or(size * 1.2)
+    }
+
     pub fn glyph_texture(&self, bundle: &super::bundle::Bundle, character: char,
         size: f32
-    ) -> Texture {
+    ) -> Result<Texture> {
         let (metrics, bitmap) = self.font.rasterize(character, size);
 
         let bytes: Vec<u8> = bitmap;
         let dimensions = (metrics.width as u32, metrics.height as u32);
 
-        let texture = Texture::from_r8u_bytes(
+        Texture::from_r8u_bytes(
             bundle,
             &bytes,
             dimensions,
             "Font Texture"
-        ).unwrap();
-        texture
+        )
     }
 }
 
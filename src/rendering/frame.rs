@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use super::bundle::SurfaceTexture;
+use super::{RenderPass, Texture};
+
+/// A frame being rendered, created by Bundle::begin_frame.
+/// Owns the surface texture, its view and an encoder until finish is called.
+/// With MSAA, render passes render into the multisampled target and resolve it into the view.
+pub struct Frame {
+    surface_texture: SurfaceTexture,
+    view: wgpu::TextureView,
+    msaa: Option<Arc<Texture>>,
+    encoder: wgpu::CommandEncoder,
+    queue: Arc<wgpu::Queue>,
+}
+
+impl Frame {
+    pub(crate) fn new(
+        surface_texture: SurfaceTexture,
+        msaa: Option<Arc<Texture>>,
+        encoder: wgpu::CommandEncoder,
+        queue: Arc<wgpu::Queue>,
+    ) -> Self {
+        let view = surface_texture
+            .texture()
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            surface_texture,
+            view,
+            msaa,
+            encoder,
+            queue,
+        }
+    }
+
+    /// Returns its surface texture.
+    pub fn surface_texture(&self) -> &SurfaceTexture {
+        &self.surface_texture
+    }
+
+    /// Returns a view of its surface texture.
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Returns its view, the surface texture and encoder are dropped.
+    pub(crate) fn into_view(self) -> wgpu::TextureView {
+        self.view
+    }
+
+    /// Returns a view of the multisampled target, None if MSAA is off.
+    pub fn msaa_view(&self) -> Option<&wgpu::TextureView> {
+        self.msaa.as_deref().map(Texture::view)
+    }
+
+    /// Returns its encoder.
+    pub fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        &mut self.encoder
+    }
+
+    /// Returns its encoder and view together, used to begin custom render passes.
+    pub fn encoder_and_view(&mut self) -> (&mut wgpu::CommandEncoder, &wgpu::TextureView) {
+        (&mut self.encoder, &self.view)
+    }
+
+    /// Begins a render pass into its view which clears it and the depth texture.
+    pub fn render_pass<'a>(
+        &'a mut self,
+        background_color: [f64; 4],
+        depth_texture_view: &'a wgpu::TextureView,
+    ) -> RenderPass<'a> {
+        self.begin_render_pass(background_color, Some(depth_texture_view))
+    }
+
+    /// Begins a render pass into its view which clears it, without a depth texture.
+    pub fn render_pass_without_depth(&mut self, background_color: [f64; 4]) -> RenderPass<'_> {
+        self.begin_render_pass(background_color, None)
+    }
+
+    fn begin_render_pass<'a>(
+        &'a mut self,
+        background_color: [f64; 4],
+        depth_texture_view: Option<&'a wgpu::TextureView>,
+    ) -> RenderPass<'a> {
+        match self.msaa.as_deref() {
+            Some(msaa) => RenderPass::begin_multisampled(
+                &mut self.encoder,
+                msaa.view(),
+                Some(&self.view),
+                background_color,
+                depth_texture_view,
+            ),
+            None => RenderPass::begin_multisampled(
+                &mut self.encoder,
+                &self.view,
+                None,
+                background_color,
+                depth_texture_view,
+            ),
+        }
+    }
+
+    /// Submits its encoder to the queue and presents the surface texture.
+    pub fn finish(self) {
+        self.queue.submit(std::iter::once(self.encoder.finish()));
+        self.surface_texture.present();
+    }
+}
@@ -0,0 +1,303 @@
+use image::imageops::FilterType;
+use image::{ImageBuffer, Pixel};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::bundle::Bundle;
+use crate::{Error, Result};
+
+/// How mip levels of a texture are created on upload.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mipmaps {
+    /// Only the base level.
+    #[default]
+    None,
+    /// A full mip chain rendered by blit passes on the GPU.
+    /// Formats which can't be rendered into or filtered are generated on the CPU.
+    Generate,
+    /// A full mip chain resized on the CPU.
+    GenerateCpu,
+}
+
+impl Mipmaps {
+    /// Returns the number of levels of a full mip chain.
+    pub fn level_count(dimensions: (u32, u32)) -> u32 {
+        32 - dimensions.0.max(dimensions.1).max(1).leading_zeros()
+    }
+
+    /// Returns dimensions of a mip level.
+    pub fn level_dimensions(dimensions: (u32, u32), level: u32) -> (u32, u32) {
+        ((dimensions.0 >> level).max(1), (dimensions.1 >> level).max(1))
+    }
+}
+
+/// Returns true if mip levels of the format can be generated by blit passes.
+pub(crate) fn can_blit(format: wgpu::TextureFormat) -> bool {
+    let info = format.describe();
+    !info.is_compressed()
+        && info
+            .guaranteed_format_features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+        && info.sample_type == wgpu::TextureSampleType::Float { filterable: true }
+}
+
+/// A pipeline which renders a view into a target of its format, sampling it with a linear filter.
+pub struct BlitPipeline {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl BlitPipeline {
+    /// Creates a new pipeline for targets of the format, use BlitCache to share it.
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Blit Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/blit.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("blit_bindgroup_layout"),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blit Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Blit Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Blit Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+        }
+    }
+
+    /// Records a render pass which replaces the target with the base level of the source.
+    pub fn blit(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        target: &wgpu::TextureView,
+    ) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: Some("blit_bind_group"),
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blit Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Shares blit pipelines of each target format, every Bundle has one.
+#[derive(Default)]
+pub struct BlitCache {
+    pipelines: Mutex<HashMap<wgpu::TextureFormat, Arc<BlitPipeline>>>,
+}
+
+impl BlitCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a pipeline for the format, it is created only if the cache does not have it yet.
+    pub fn get(&self, device: &wgpu::Device, format: wgpu::TextureFormat) -> Arc<BlitPipeline> {
+        self.pipelines
+            .lock()
+            .unwrap()
+            .entry(format)
+            .or_insert_with(|| Arc::new(BlitPipeline::new(device, format)))
+            .clone()
+    }
+
+    /// Returns the number of cached pipelines.
+    pub fn len(&self) -> usize {
+        self.pipelines.lock().unwrap().len()
+    }
+
+    /// Returns true if no pipeline is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops cached pipelines.
+    pub fn clear(&self) {
+        self.pipelines.lock().unwrap().clear();
+    }
+}
+
+/// Renders every mip level of a layer of the texture from the previous one, source is a view of its base level.
+/// The GL backend can't sample a single mip level, so there levels are also rendered into
+/// intermediate textures which are sampled for the next level.
+/// The texture has to be created with `TextureUsages::RENDER_ATTACHMENT`.
+pub(crate) fn generate_gpu(
+    bundle: &Bundle,
+    source: &wgpu::TextureView,
+    texture: &wgpu::Texture,
+    layer: u32,
+    format: wgpu::TextureFormat,
+    dimensions: (u32, u32),
+    mip_level_count: u32,
+) {
+    let device = bundle.device();
+    let blit = bundle.blit_cache().get(device, format);
+    let intermediate = bundle
+        .adapter_info()
+        .is_some_and(|info| info.backend == wgpu::Backend::Gl);
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Mipmap Encoder"),
+    });
+    // Keeps intermediate textures alive until the encoder is submitted.
+    let mut previous: Option<(Option<wgpu::Texture>, wgpu::TextureView)> = None;
+    for level in 1..mip_level_count {
+        let level_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Mip Level View"),
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            base_mip_level: level,
+            mip_level_count: std::num::NonZeroU32::new(1),
+            base_array_layer: layer,
+            array_layer_count: std::num::NonZeroU32::new(1),
+            ..Default::default()
+        });
+        let source = previous.as_ref().map_or(source, |(_, view)| view);
+        blit.blit(device, &mut encoder, source, &level_view);
+
+        previous = Some(if intermediate {
+            let (width, height) = Mipmaps::level_dimensions(dimensions, level);
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Mip Level Texture"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            blit.blit(device, &mut encoder, source, &view);
+            (Some(texture), view)
+        } else {
+            (None, level_view)
+        });
+    }
+    bundle.queue().submit(std::iter::once(encoder.finish()));
+}
+
+/// Returns bytes of mip levels after the base level, resized with image::imageops::resize.
+/// Supports uncompressed formats with one byte per component.
+///
+/// # Panics
+/// Panics if bytes are shorter than the dimensions.
+pub(crate) fn generate_cpu(
+    format: wgpu::TextureFormat,
+    bytes: &[u8],
+    dimensions: (u32, u32),
+    bytes_per_pixel: u8,
+) -> Result<Vec<Vec<u8>>> {
+    let info = format.describe();
+    if info.is_compressed() || info.block_size != bytes_per_pixel || info.components != bytes_per_pixel {
+        return Err(Error::UnsupportedFormat(format));
+    }
+
+    match bytes_per_pixel {
+        1 => Ok(downsample::<image::Luma<u8>>(bytes, dimensions)),
+        2 => Ok(downsample::<image::LumaA<u8>>(bytes, dimensions)),
+        4 => Ok(downsample::<image::Rgba<u8>>(bytes, dimensions)),
+        _ => Err(Error::UnsupportedFormat(format)),
+    }
+}
+
+fn downsample<P: Pixel<Subpixel = u8> + 'static>(bytes: &[u8], dimensions: (u32, u32)) -> Vec<Vec<u8>> {
+    let length = (dimensions.0 * dimensions.1) as usize * P::CHANNEL_COUNT as usize;
+    let mut image = ImageBuffer::<P, Vec<u8>>::from_raw(dimensions.0, dimensions.1, bytes[..length].to_vec())
+        .expect("bytes match the texture dimensions");
+
+    (1..Mipmaps::level_count(dimensions))
+        .map(|level| {
+            let (width, height) = Mipmaps::level_dimensions(dimensions, level);
+            image = image::imageops::resize(&image, width, height, FilterType::Triangle);
+            image.as_raw().clone()
+        })
+        .collect()
+}
@@ -12,6 +12,9 @@ pub use render_queue::*;
 pub mod render_pass;
 pub use render_pass::*;
 
+pub mod frame;
+pub use frame::*;
+
 pub mod render_pipeline;
 pub use render_pipeline::*;
 
@@ -24,6 +27,20 @@ pub use indices::*;
 pub mod texture;
 pub use texture::*;
 
+pub mod mipmaps;
+pub use mipmaps::*;
+
+pub mod sampler;
+pub use sampler::*;
+
+pub mod cubemap;
+pub use cubemap::*;
+
+pub mod compressed;
+pub use compressed::*;
+
+mod bcn;
+
 pub mod mesh;
 pub use mesh::*;
 
@@ -44,3 +61,6 @@ pub use uniform::*;
 
 pub mod instance;
 pub use instance::*;
+
+pub mod stats_overlay;
+pub use stats_overlay::*;
@@ -1,4 +1,4 @@
-use anyhow::*;
+use crate::{Error, Result};
 use wgpu::util::DeviceExt;
 
 pub struct Model {
@@ -22,7 +22,7 @@ impl Model {
         bundle: &super::bundle::Bundle,
         layout: &wgpu::BindGroupLayout
     ) -> Result<Self> {
-        let obj_text = std::fs::read_to_string(path).unwrap();
+        let obj_text = std::fs::read_to_string(path).map_err(|e| Error::missing_file(path, e))?;
         let obj_cursor = std::io::Cursor::new(obj_text);
         let mut obj_reader = std::io::BufReader::new(obj_cursor);
 
@@ -34,15 +34,16 @@ impl Model {
                 ..Default::default()
             },
             |p| {
-                let mat_text = std::fs::read_to_string(&p).unwrap();
+                let mat_text = std::fs::read_to_string(&p)
+                    .map_err(|_| tobj::LoadError::OpenFileFailed)?;
                 tobj::load_mtl_buf(&mut std::io::BufReader::new(std::io::Cursor::new(mat_text)))
             },
-        ).unwrap();
+        ).map_err(Error::ObjParse)?;
 
         let mut materials = Vec::new();
-        for m in obj_materials.unwrap() {
+        for m in obj_materials.map_err(Error::MtlParse)? {
             let diffuse_texture = super::Texture::from_path(
-                bundle, &m.diffuse_texture, "model_texture").unwrap();
+                bundle, &m.diffuse_texture, "model_texture")?;
             let bind_group = bundle.device().create_bind_group(&wgpu::BindGroupDescriptor {
                 layout,
                 entries: &[
@@ -10,27 +10,7 @@ impl<'a> RenderPass<'a> {
         view: &'a wgpu::TextureView,
         background_color: [f64; 4],
     ) -> Self {
-        let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: background_color[0],
-                        g: background_color[1],
-                        b: background_color[2],
-                        a: background_color[3],
-                    }),
-                    store: true,
-                },
-            })],
-            depth_stencil_attachment: None,
-        });
-
-        Self {
-            render_pass,
-        }
+        Self::begin_multisampled(encoder, view, None, background_color, None)
     }
 
     pub fn begin(
@@ -38,12 +18,24 @@ impl<'a> RenderPass<'a> {
         view: &'a wgpu::TextureView,
         background_color: [f64; 4],
         depth_texture_view: &'a wgpu::TextureView,
+    ) -> Self {
+        Self::begin_multisampled(encoder, view, None, background_color, Some(depth_texture_view))
+    }
+
+    /// Begins a render pass into a multisampled view which is resolved into resolve_target.
+    /// Without a resolve target it is the same as begin or begin_without_depth.
+    pub fn begin_multisampled(
+        encoder: &'a mut wgpu::CommandEncoder,
+        view: &'a wgpu::TextureView,
+        resolve_target: Option<&'a wgpu::TextureView>,
+        background_color: [f64; 4],
+        depth_texture_view: Option<&'a wgpu::TextureView>,
     ) -> Self {
         let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Render Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
-                resolve_target: None,
+                view,
+                resolve_target,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Clear(wgpu::Color {
                         r: background_color[0],
@@ -54,8 +46,8 @@ impl<'a> RenderPass<'a> {
                     store: true,
                 },
             })],
-            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                view: depth_texture_view,
+            depth_stencil_attachment: depth_texture_view.map(|view| wgpu::RenderPassDepthStencilAttachment {
+                view,
                 depth_ops: Some(wgpu::Operations {
                     load: wgpu::LoadOp::Clear(1.0),
                     store: true,
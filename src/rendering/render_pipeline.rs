@@ -10,6 +10,19 @@ impl RenderPipeline {
         vertex_buffer_layouts: &[wgpu::VertexBufferLayout],
         format: wgpu::TextureFormat,
         depth_stencil: Option<wgpu::DepthStencilState>,
+    ) -> Self {
+        Self::new_multisampled(device, shader, bind_group_layouts, vertex_buffer_layouts, format, depth_stencil, 1)
+    }
+
+    /// Creates a pipeline rendering into targets with the sample count, see Bundle::sample_count.
+    pub fn new_multisampled(
+        device: &wgpu::Device,
+        shader: &str,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+        vertex_buffer_layouts: &[wgpu::VertexBufferLayout],
+        format: wgpu::TextureFormat,
+        depth_stencil: Option<wgpu::DepthStencilState>,
+        sample_count: u32,
     ) -> Self {
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Shader"),
@@ -53,7 +66,7 @@ impl RenderPipeline {
             },
             depth_stencil: depth_stencil,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
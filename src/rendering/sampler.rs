@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::num::NonZeroU8;
+use std::sync::{Arc, Mutex};
+
+/// Describes a sampler of a texture.
+/// The default clamps to edge, magnifies linearly and minifies with the nearest texel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerOptions {
+    pub address_mode_u: wgpu::AddressMode,
+    pub address_mode_v: wgpu::AddressMode,
+    pub address_mode_w: wgpu::AddressMode,
+    pub mag_filter: wgpu::FilterMode,
+    pub min_filter: wgpu::FilterMode,
+    pub mipmap_filter: wgpu::FilterMode,
+    pub lod_min_clamp: f32,
+    pub lod_max_clamp: f32,
+    /// Makes it a comparison sampler, used for depth textures.
+    pub compare: Option<wgpu::CompareFunction>,
+    /// Maximal anisotropy, normalized like with_anisotropy when the sampler is created.
+    pub anisotropy_clamp: Option<NonZeroU8>,
+    /// Color outside of the texture with `AddressMode::ClampToBorder`.
+    pub border_color: Option<wgpu::SamplerBorderColor>,
+}
+
+impl Default for SamplerOptions {
+    fn default() -> Self {
+        Self {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: f32::MAX,
+            compare: None,
+            anisotropy_clamp: None,
+            border_color: None,
+        }
+    }
+}
+
+impl SamplerOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates options filtering with the nearest texel everywhere, for pixel art.
+    pub fn nearest() -> Self {
+        Self::default().with_filter(wgpu::FilterMode::Nearest)
+    }
+
+    /// Creates options filtering linearly everywhere, mipmapped textures are sampled trilinearly.
+    pub fn trilinear() -> Self {
+        Self::default().with_filter(wgpu::FilterMode::Linear)
+    }
+
+    /// Sets the address mode of all coordinates, Repeat tiles the texture.
+    pub fn with_address_mode(mut self, address_mode: wgpu::AddressMode) -> Self {
+        self.address_mode_u = address_mode;
+        self.address_mode_v = address_mode;
+        self.address_mode_w = address_mode;
+        self
+    }
+
+    /// Sets the magnification, minification and mipmap filters.
+    pub fn with_filter(mut self, filter: wgpu::FilterMode) -> Self {
+        self.mag_filter = filter;
+        self.min_filter = filter;
+        self.mipmap_filter = filter;
+        self
+    }
+
+    /// Sets the maximal anisotropy, 0 and 1 turn anisotropic filtering off.
+    /// Other values are rounded down to a power of two up to 16 and set all filters to Linear.
+    pub fn with_anisotropy(mut self, anisotropy_clamp: u8) -> Self {
+        let anisotropy_clamp = match anisotropy_clamp {
+            0 | 1 => None,
+            2..=3 => NonZeroU8::new(2),
+            4..=7 => NonZeroU8::new(4),
+            8..=15 => NonZeroU8::new(8),
+            _ => NonZeroU8::new(16),
+        };
+        self.anisotropy_clamp = anisotropy_clamp;
+        if anisotropy_clamp.is_some() {
+            self = self.with_filter(wgpu::FilterMode::Linear);
+        }
+        self
+    }
+
+    /// Sets the range of mip levels which can be sampled.
+    pub fn with_lod_clamp(mut self, lod_min_clamp: f32, lod_max_clamp: f32) -> Self {
+        self.lod_min_clamp = lod_min_clamp;
+        self.lod_max_clamp = lod_max_clamp;
+        self
+    }
+
+    /// Sets the compare function, None for a filtering sampler.
+    pub fn with_compare(mut self, compare: Option<wgpu::CompareFunction>) -> Self {
+        self.compare = compare;
+        self
+    }
+
+    /// Sets the border color used with `AddressMode::ClampToBorder`.
+    pub fn with_border_color(mut self, border_color: Option<wgpu::SamplerBorderColor>) -> Self {
+        self.border_color = border_color;
+        self
+    }
+
+    /// Returns a descriptor of the sampler.
+    /// The anisotropy clamp is normalized like with_anisotropy, it sets all filters to Linear.
+    pub fn descriptor<'a>(&self, label: Option<&'a str>) -> wgpu::SamplerDescriptor<'a> {
+        let options = self.normalized();
+        wgpu::SamplerDescriptor {
+            label,
+            address_mode_u: options.address_mode_u,
+            address_mode_v: options.address_mode_v,
+            address_mode_w: options.address_mode_w,
+            mag_filter: options.mag_filter,
+            min_filter: options.min_filter,
+            mipmap_filter: options.mipmap_filter,
+            lod_min_clamp: options.lod_min_clamp,
+            lod_max_clamp: options.lod_max_clamp,
+            compare: options.compare,
+            anisotropy_clamp: options.anisotropy_clamp,
+            border_color: options.border_color,
+        }
+    }
+
+    /// Creates a new sampler, use SamplerCache to share it.
+    pub fn create_sampler(&self, device: &wgpu::Device) -> wgpu::Sampler {
+        device.create_sampler(&self.descriptor(Some("Sampler")))
+    }
+
+    /// Returns the options with the anisotropy clamp rounded down to a power of two up to 16.
+    fn normalized(&self) -> Self {
+        match self.anisotropy_clamp {
+            Some(clamp) => self.with_anisotropy(clamp.get()),
+            None => *self,
+        }
+    }
+
+    fn key(&self) -> SamplerKey {
+        let options = self.normalized();
+        SamplerKey {
+            address_modes: [options.address_mode_u, options.address_mode_v, options.address_mode_w],
+            filters: [options.mag_filter, options.min_filter, options.mipmap_filter],
+            lod_clamp: [options.lod_min_clamp.to_bits(), options.lod_max_clamp.to_bits()],
+            compare: options.compare,
+            anisotropy_clamp: options.anisotropy_clamp,
+            border_color: options.border_color,
+        }
+    }
+}
+
+/// SamplerOptions with LOD clamps compared by bits, so they can be hashed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct SamplerKey {
+    address_modes: [wgpu::AddressMode; 3],
+    filters: [wgpu::FilterMode; 3],
+    lod_clamp: [u32; 2],
+    compare: Option<wgpu::CompareFunction>,
+    anisotropy_clamp: Option<NonZeroU8>,
+    border_color: Option<wgpu::SamplerBorderColor>,
+}
+
+/// Shares samplers with identical options, every Bundle has one.
+#[derive(Default)]
+pub struct SamplerCache {
+    samplers: Mutex<HashMap<SamplerKey, Arc<wgpu::Sampler>>>,
+}
+
+impl SamplerCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a sampler with the options, it is created only if the cache does not have it yet.
+    pub fn get(&self, device: &wgpu::Device, options: &SamplerOptions) -> Arc<wgpu::Sampler> {
+        self.samplers
+            .lock()
+            .unwrap()
+            .entry(options.key())
+            .or_insert_with(|| Arc::new(options.create_sampler(device)))
+            .clone()
+    }
+
+    /// Returns the number of cached samplers.
+    pub fn len(&self) -> usize {
+        self.samplers.lock().unwrap().len()
+    }
+
+    /// Returns true if no sampler is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops cached samplers, textures keep theirs alive.
+    pub fn clear(&self) {
+        self.samplers.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,218 @@
+use super::*;
+use crate::main_loop::FrameStats;
+use crate::Result;
+
+struct Glyph {
+    metrics: fontdue::Metrics,
+    texture: Option<(Texture, wgpu::BindGroup)>,
+}
+
+/// On-screen overlay drawing FrameStats with the text pipeline.
+/// Its buffers are created once, text longer than MAX_GLYPHS is cut off.
+pub struct StatsOverlay {
+    font: Font,
+    size: f32,
+    position: glam::Vec2,
+    color: glam::Vec4,
+    pipeline: RenderPipeline,
+    sample_count: u32,
+    glyphs: Atlas<char, Glyph>,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    draws: Vec<(usize, std::ops::Range<u32>)>,
+    mvp_buffer: wgpu::Buffer,
+    mvp_bind_group: wgpu::BindGroup,
+    color_buffer: wgpu::Buffer,
+    color_bind_group: wgpu::BindGroup,
+}
+
+impl StatsOverlay {
+    /// The maximal number of drawn glyphs.
+    pub const MAX_GLYPHS: usize = 4096;
+
+    /// Creates a new overlay drawing text of the font with size in pixels.
+    pub fn new(bundle: &bundle::Bundle, font: Font, size: f32) -> Self {
+        let color = glam::vec4(1.0, 1.0, 0.0, 1.0);
+        let mvp_buffer = Uniform::new(glam::Mat4::IDENTITY).buffer(bundle);
+        let color_buffer = Uniform::new(color).buffer(bundle);
+
+        Self {
+            font,
+            size,
+            position: glam::vec2(8.0, 8.0),
+            color,
+            pipeline: bundle.text_pipeline(),
+            sample_count: bundle.sample_count(),
+            glyphs: Atlas::new(),
+            vertex_buffer: Self::buffer(
+                bundle,
+                "Stats Overlay Vertex Buffer",
+                Self::MAX_GLYPHS * 4 * std::mem::size_of::<TextureVertex>(),
+                wgpu::BufferUsages::VERTEX,
+            ),
+            index_buffer: Self::buffer(
+                bundle,
+                "Stats Overlay Index Buffer",
+                Self::MAX_GLYPHS * 6 * std::mem::size_of::<u16>(),
+                wgpu::BufferUsages::INDEX,
+            ),
+            draws: vec![],
+            mvp_bind_group: Self::uniform_bind_group(bundle, &mvp_buffer),
+            mvp_buffer,
+            color_bind_group: Self::uniform_bind_group(bundle, &color_buffer),
+            color_buffer,
+        }
+    }
+
+    fn buffer(bundle: &bundle::Bundle, label: &str, size: usize, usage: wgpu::BufferUsages) -> wgpu::Buffer {
+        bundle.device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: size as wgpu::BufferAddress,
+            usage: usage | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn uniform_bind_group(bundle: &bundle::Bundle, buffer: &wgpu::Buffer) -> wgpu::BindGroup {
+        bundle.device().create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &Uniform::<glam::Mat4>::bind_group_layout(bundle),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+            label: Some("Uniform bind group"),
+        })
+    }
+
+    /// Returns its text color.
+    pub fn color(&self) -> glam::Vec4 {
+        self.color
+    }
+
+    /// Sets its text color.
+    pub fn set_color(&mut self, bundle: &bundle::Bundle, color: glam::Vec4) {
+        self.color = color;
+        bundle
+            .queue()
+            .write_buffer(&self.color_buffer, 0, Uniform::new(color).to_bytes());
+    }
+
+    /// Returns the position of its top left corner in pixels.
+    pub fn position(&self) -> glam::Vec2 {
+        self.position
+    }
+
+    /// Sets the position of its top left corner in pixels.
+    pub fn set_position(&mut self, position: glam::Vec2) {
+        self.position = position;
+    }
+
+    /// Updates the drawn text with the stats.
+    /// Should be called every frame before render.
+    pub fn update(&mut self, bundle: &bundle::Bundle, stats: &FrameStats) -> Result<()> {
+        self.update_text(bundle, &stats.to_string())
+    }
+
+    /// Updates the drawn text.
+    /// Its pipeline is recreated if the sample count of the Bundle has changed.
+    pub fn update_text(&mut self, bundle: &bundle::Bundle, text: &str) -> Result<()> {
+        if bundle.sample_count() != self.sample_count {
+            self.pipeline = bundle.text_pipeline();
+            self.sample_count = bundle.sample_count();
+        }
+
+        let mut vertices = vec![];
+        let mut indices: Vec<u16> = vec![];
+        self.draws.clear();
+
+        let line_height = self.font.line_height(self.size);
+        let mut baseline = self.position.y + self.size;
+
+        for line in text.lines() {
+            let mut pen = self.position.x;
+
+            for character in line.chars() {
+                let index = self.glyph(bundle, character)?;
+                let glyph = self.glyphs.value(index);
+                let metrics = glyph.metrics;
+
+                if glyph.texture.is_some() && self.draws.len() < Self::MAX_GLYPHS {
+                    let left = pen + metrics.xmin as f32;
+                    let right = left + metrics.width as f32;
+                    let bottom = baseline - metrics.ymin as f32;
+                    let top = bottom - metrics.height as f32;
+
+                    let first = vertices.len() as u16;
+                    vertices.extend_from_slice(&[
+                        TextureVertex::new(glam::vec3(left, top, 0.0), glam::vec2(0.0, 0.0)),
+                        TextureVertex::new(glam::vec3(left, bottom, 0.0), glam::vec2(0.0, 1.0)),
+                        TextureVertex::new(glam::vec3(right, bottom, 0.0), glam::vec2(1.0, 1.0)),
+                        TextureVertex::new(glam::vec3(right, top, 0.0), glam::vec2(1.0, 0.0)),
+                    ]);
+
+                    let start = indices.len() as u32;
+                    indices.extend_from_slice(&[
+                        first, first + 1, first + 2,
+                        first, first + 2, first + 3,
+                    ]);
+                    self.draws.push((index, start..indices.len() as u32));
+                }
+
+                pen += metrics.advance_width;
+            }
+
+            baseline += line_height;
+        }
+
+        if !vertices.is_empty() {
+            bundle
+                .queue()
+                .write_buffer(&self.vertex_buffer, 0, Vertices::new(vertices).to_bytes());
+            bundle
+                .queue()
+                .write_buffer(&self.index_buffer, 0, Indices::new(indices).to_bytes());
+        }
+
+        let (width, height) = (bundle.config().width as f32, bundle.config().height as f32);
+        let projection = glam::Mat4::orthographic_rh(0.0, width, height, 0.0, 0.0, 1.0);
+        bundle
+            .queue()
+            .write_buffer(&self.mvp_buffer, 0, Uniform::new(projection).to_bytes());
+
+        Ok(())
+    }
+
+    /// Draws the text into a render pass with a depth attachment.
+    pub fn render<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        for (index, range) in &self.draws {
+            if let Some((_, bind_group)) = &self.glyphs.value(*index).texture {
+                render_pass.render_text(
+                    self.vertex_buffer.slice(..),
+                    self.index_buffer.slice(..),
+                    range.clone(),
+                    bind_group,
+                    &self.mvp_bind_group,
+                    &self.color_bind_group,
+                    &self.pipeline,
+                );
+            }
+        }
+    }
+
+    fn glyph(&mut self, bundle: &bundle::Bundle, character: char) -> Result<usize> {
+        if self.glyphs.contains(&character) {
+            return Ok(self.glyphs.index(&character));
+        }
+
+        let metrics = self.font.metrics(character, self.size);
+        let texture = if metrics.width > 0 && metrics.height > 0 {
+            let texture = self.font.glyph_texture(bundle, character, self.size)?;
+            let bind_group = texture.bind_group(bundle);
+            Some((texture, bind_group))
+        } else {
+            None
+        };
+
+        Ok(self.glyphs.add(character, Glyph { metrics, texture }))
+    }
+}
@@ -1,12 +1,25 @@
 use std::io::{BufReader, Cursor};
 
+use std::sync::Arc;
+
 use image::GenericImageView;
-use anyhow::*;
+use crate::{Error, Result};
+
+use super::{Mipmaps, SamplerOptions, TextureContainer};
+
+/// Options of a texture created from data.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TextureOptions {
+    pub mipmaps: Mipmaps,
+    /// None samples textures with mip levels trilinearly and others with SamplerOptions::default.
+    pub sampler: Option<SamplerOptions>,
+}
 
 pub struct Texture {
     texture: wgpu::Texture,
     view: wgpu::TextureView,
-    sampler: wgpu::Sampler,
+    sampler: Arc<wgpu::Sampler>,
+    view_dimension: wgpu::TextureViewDimension,
 }
 
 impl Texture {
@@ -38,34 +51,61 @@ impl Texture {
         &self.sampler
     }
 
-    pub fn sampler_mut(&mut self) -> &mut wgpu::Sampler {
+    /// Samplers can be shared with other textures, see Bundle::sampler.
+    pub fn sampler_mut(&mut self) -> &mut Arc<wgpu::Sampler> {
         &mut self.sampler
     }
 
-    pub fn set_sampler(&mut self, sampler: wgpu::Sampler) {
-        self.sampler = sampler;
+    pub fn set_sampler(&mut self, sampler: impl Into<Arc<wgpu::Sampler>>) {
+        self.sampler = sampler.into();
     }
 
-    pub fn new(texture: wgpu::Texture, view: wgpu::TextureView, sampler: wgpu::Sampler
+    /// Returns the dimension of its view, bind_group uses a layout with it.
+    pub fn view_dimension(&self) -> wgpu::TextureViewDimension {
+        self.view_dimension
+    }
+
+    /// Sets the dimension of its view, it has to match the view.
+    pub fn set_view_dimension(&mut self, view_dimension: wgpu::TextureViewDimension) {
+        self.view_dimension = view_dimension;
+    }
+
+    /// Creates a texture with a D2 view.
+    pub fn new(texture: wgpu::Texture, view: wgpu::TextureView, sampler: impl Into<Arc<wgpu::Sampler>>
     ) -> Self {
         Self {
             texture,
             view,
-            sampler,
+            sampler: sampler.into(),
+            view_dimension: wgpu::TextureViewDimension::D2,
         }
     }
 
     pub fn from_path(bundle: &super::bundle::Bundle, path: &str, label: &str
     ) -> Result<Self> {
-        let bytes = std::fs::read(path).unwrap();
-        Self::from_bytes(bundle, &bytes, label)
+        Self::from_path_with_options(bundle, path, &TextureOptions::default(), label)
+    }
+
+    /// Loads a texture from an image file with the options.
+    pub fn from_path_with_options(bundle: &super::bundle::Bundle, path: &str,
+        options: &TextureOptions, label: &str
+    ) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| Error::missing_file(path, e))?;
+        Self::from_bytes_with_options(bundle, &bytes, options, label)
     }
 
     pub fn from_bytes(bundle: &super::bundle::Bundle, bytes: &[u8], label: &str
     ) -> Result<Self> {
+        Self::from_bytes_with_options(bundle, bytes, &TextureOptions::default(), label)
+    }
 
-        let img = image::load_from_memory(bytes).unwrap();
-        Self::from_image(bundle, &img, label)
+    /// Decodes a texture from bytes of an image file with the options.
+    pub fn from_bytes_with_options(bundle: &super::bundle::Bundle, bytes: &[u8],
+        options: &TextureOptions, label: &str
+    ) -> Result<Self> {
+
+        let img = image::load_from_memory(bytes).map_err(Error::ImageDecode)?;
+        Self::from_image_with_options(bundle, &img, options, label)
     }
 
     pub fn empty(bundle: &super::bundle::Bundle,
@@ -79,179 +119,433 @@ impl Texture {
     pub fn from_image(bundle: &super::bundle::Bundle, 
         img: &image::DynamicImage, label: &str) -> Result<Self> {
 
+        Self::from_image_with_mipmaps(bundle, img, Mipmaps::None, label)
+    }
+
+    /// Creates a texture from an image, with mip levels generated as selected.
+    pub fn from_image_with_mipmaps(bundle: &super::bundle::Bundle,
+        img: &image::DynamicImage, mipmaps: Mipmaps, label: &str) -> Result<Self> {
+
+        Self::from_image_with_options(bundle, img, &TextureOptions { mipmaps, sampler: None }, label)
+    }
+
+    /// Creates a texture from an image with the options.
+    pub fn from_image_with_options(bundle: &super::bundle::Bundle,
+        img: &image::DynamicImage, options: &TextureOptions, label: &str) -> Result<Self> {
+
         let rgba = img.to_rgba8();
-        let dimensions = img.dimensions();
 
-        let size = wgpu::Extent3d {
-            width: dimensions.0,
-            height: dimensions.1,
-            depth_or_array_layers: 1,
-        };
+        Self::from_raw(
+            bundle,
+            label,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            &rgba,
+            img.dimensions(),
+            4,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            options,
+        )
+    }
+
+    pub fn from_r8u_bytes(
+        bundle: &super::bundle::Bundle,
+        bytes: &[u8], dimensions: (u32, u32),
+        label: &str
+        ) -> Result<Self> {
+
+        Self::from_r8u_bytes_with_mipmaps(bundle, bytes, dimensions, Mipmaps::None, label)
+    }
+
+    /// Creates an R8Unorm texture, with mip levels generated as selected.
+    pub fn from_r8u_bytes_with_mipmaps(
+        bundle: &super::bundle::Bundle,
+        bytes: &[u8], dimensions: (u32, u32),
+        mipmaps: Mipmaps,
+        label: &str
+        ) -> Result<Self> {
+
+        Self::from_r8u_bytes_with_options(bundle, bytes, dimensions, &TextureOptions { mipmaps, sampler: None }, label)
+    }
+
+    /// Creates an R8Unorm texture with the options.
+    pub fn from_r8u_bytes_with_options(
+        bundle: &super::bundle::Bundle,
+        bytes: &[u8], dimensions: (u32, u32),
+        options: &TextureOptions,
+        label: &str
+        ) -> Result<Self> {
+
+        Self::from_raw(
+            bundle,
+            label,
+            wgpu::TextureFormat::R8Unorm,
+            bytes,
+            dimensions,
+            1,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            options,
+        )
+    }
+
+    pub fn from_bytes_custom(
+        bundle: &super::bundle::Bundle,
+        label: &str,
+        format: wgpu::TextureFormat, bytes: &[u8], dimensions: (u32, u32), bytes_per_pixel: u8,
+        usage: wgpu::TextureUsages) -> Result<Self> {
+
+        Self::from_raw(bundle, label, format, bytes, dimensions, bytes_per_pixel, usage, &TextureOptions::default())
+    }
+
+    /// Creates a texture of any uncompressed format, with mip levels generated as selected.
+    /// Generating on the CPU needs one byte per component, other formats return Error::UnsupportedFormat.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_bytes_custom_with_mipmaps(
+        bundle: &super::bundle::Bundle,
+        label: &str,
+        format: wgpu::TextureFormat, bytes: &[u8], dimensions: (u32, u32), bytes_per_pixel: u8,
+        usage: wgpu::TextureUsages, mipmaps: Mipmaps) -> Result<Self> {
+
+        let options = TextureOptions { mipmaps, sampler: None };
+        Self::from_raw(bundle, label, format, bytes, dimensions, bytes_per_pixel, usage, &options)
+    }
+
+    /// Creates a texture of any uncompressed format with the options.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_bytes_custom_with_options(
+        bundle: &super::bundle::Bundle,
+        label: &str,
+        format: wgpu::TextureFormat, bytes: &[u8], dimensions: (u32, u32), bytes_per_pixel: u8,
+        usage: wgpu::TextureUsages, options: &TextureOptions) -> Result<Self> {
+
+        Self::from_raw(bundle, label, format, bytes, dimensions, bytes_per_pixel, usage, options)
+    }
+
+    /// Creates a cube texture from six square images of the same size, in the order of CubeFace::ALL.
+    pub fn from_cube_images(bundle: &super::bundle::Bundle,
+        faces: &[image::DynamicImage; 6], options: &TextureOptions, label: &str) -> Result<Self> {
+
+        let (width, height) = faces[0].dimensions();
+        if width != height {
+            return Err(Error::ImageSizeMismatch { expected: (width, width), found: (width, height) });
+        }
+        Self::from_image_layers(bundle, faces, wgpu::TextureViewDimension::Cube, options, label)
+    }
 
-        let texture_format = wgpu::TextureFormat::Rgba8UnormSrgb;
+    /// Creates a cube texture with faces of face_size projected from an equirectangular panorama,
+    /// see equirectangular_to_cube_faces.
+    pub fn from_equirectangular(bundle: &super::bundle::Bundle,
+        img: &image::DynamicImage, face_size: u32, options: &TextureOptions, label: &str) -> Result<Self> {
+
+        let faces: Vec<_> = super::equirectangular_to_cube_faces(&img.to_rgba8(), face_size)?
+            .into_iter()
+            .map(image::DynamicImage::ImageRgba8)
+            .collect();
+        Self::from_image_layers(bundle, &faces, wgpu::TextureViewDimension::Cube, options, label)
+    }
+
+    /// Creates a 2D texture array from images of the same size, one layer per image.
+    pub fn from_image_array(bundle: &super::bundle::Bundle,
+        images: &[image::DynamicImage], options: &TextureOptions, label: &str) -> Result<Self> {
+
+        if images.is_empty() {
+            return Err(Error::EmptyTextureArray);
+        }
+        Self::from_image_layers(bundle, images, wgpu::TextureViewDimension::D2Array, options, label)
+    }
+
+    /// Loads a texture from a KTX2 or DDS file with its mip levels, see from_container.
+    pub fn from_container_path(bundle: &super::bundle::Bundle, path: &str,
+        options: &TextureOptions, label: &str
+    ) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| Error::missing_file(path, e))?;
+        Self::from_container(bundle, &TextureContainer::from_bytes(&bytes)?, options, label)
+    }
+
+    /// Creates a texture from bytes of a KTX2 file with its mip levels, see from_container.
+    pub fn from_ktx2(bundle: &super::bundle::Bundle, bytes: &[u8],
+        options: &TextureOptions, label: &str
+    ) -> Result<Self> {
+        Self::from_container(bundle, &TextureContainer::from_ktx2(bytes)?, options, label)
+    }
+
+    /// Creates a texture from bytes of a DDS file with its mip levels, see from_container.
+    pub fn from_dds(bundle: &super::bundle::Bundle, bytes: &[u8],
+        options: &TextureOptions, label: &str
+    ) -> Result<Self> {
+        Self::from_container(bundle, &TextureContainer::from_dds(bytes)?, options, label)
+    }
+
+    /// Creates a texture with the format, layers and mip levels of a container, options.mipmaps is ignored.
+    /// Formats which need features the device lacks are decompressed on the CPU, see TextureContainer::decompress.
+    /// Request `Features::TEXTURE_COMPRESSION_BC` with BundleDescriptor::optional_features to upload BC formats.
+    pub fn from_container(bundle: &super::bundle::Bundle,
+        container: &TextureContainer, options: &TextureOptions, label: &str) -> Result<Self> {
+
+        let decompressed;
+        let container = if container.is_supported(bundle.features()) {
+            container
+        } else {
+            decompressed = container.decompress()?;
+            &decompressed
+        };
+        let format = container.format;
+        let info = format.describe();
 
         let texture = bundle.device().create_texture(
             &wgpu::TextureDescriptor {
                 label: Some(label),
-                size,
-                mip_level_count: 1,
+                size: wgpu::Extent3d {
+                    width: container.dimensions.0,
+                    height: container.dimensions.1,
+                    depth_or_array_layers: container.layer_count,
+                },
+                mip_level_count: container.level_count(),
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: texture_format,
+                format,
                 usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST
-                    | wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    | wgpu::TextureUsages::COPY_SRC,
             }
         );
 
-        bundle.queue().write_texture(
-            wgpu::ImageCopyTexture {
-                aspect: wgpu::TextureAspect::All,
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-            },
-            &rgba,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: std::num::NonZeroU32::new(4 * dimensions.0),
-                rows_per_image: std::num::NonZeroU32::new(dimensions.1),
-            },
-            size,
-        );
-
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = bundle.device().create_sampler(
-            &wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Nearest,
-                mipmap_filter: wgpu::FilterMode::Nearest,
-                ..Default::default()
+        // Levels smaller than a block are copied as a whole block.
+        for level in 0..container.level_count() {
+            let (blocks_x, blocks_y) = super::block_count(format, container.level_dimensions(level));
+            for layer in 0..container.layer_count {
+                bundle.queue().write_texture(
+                    wgpu::ImageCopyTexture {
+                        aspect: wgpu::TextureAspect::All,
+                        texture: &texture,
+                        mip_level: level,
+                        origin: wgpu::Origin3d { x: 0, y: 0, z: layer },
+                    },
+                    container.layer(level, layer),
+                    wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: std::num::NonZeroU32::new(blocks_x * info.block_size as u32),
+                        rows_per_image: std::num::NonZeroU32::new(blocks_y),
+                    },
+                    wgpu::Extent3d {
+                        width: blocks_x * info.block_dimensions.0 as u32,
+                        height: blocks_y * info.block_dimensions.1 as u32,
+                        depth_or_array_layers: 1,
+                    },
+                );
             }
-        );
-        
-        Ok(Self { texture, view, sampler })
+        }
+
+        let view_dimension = container.view_dimension;
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(view_dimension),
+            ..Default::default()
+        });
+        let sampler = options.sampler.unwrap_or_else(|| if container.level_count() > 1 {
+            SamplerOptions::trilinear()
+        } else {
+            SamplerOptions::default()
+        });
+        let sampler = bundle.sampler(&sampler);
+
+        Ok(Self { texture, view, sampler, view_dimension })
     }
 
-    pub fn from_r8u_bytes(
+    fn from_image_layers(bundle: &super::bundle::Bundle,
+        images: &[image::DynamicImage], view_dimension: wgpu::TextureViewDimension,
+        options: &TextureOptions, label: &str) -> Result<Self> {
+
+        let dimensions = images[0].dimensions();
+        let layers = images
+            .iter()
+            .map(|img| match img.dimensions() {
+                found if found == dimensions => Ok(img.to_rgba8().into_raw()),
+                found => Err(Error::ImageSizeMismatch { expected: dimensions, found }),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let layers: Vec<_> = layers.iter().map(Vec::as_slice).collect();
+
+        Self::from_layers(
+            bundle,
+            label,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            &layers,
+            dimensions,
+            4,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            view_dimension,
+            options,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_raw(
         bundle: &super::bundle::Bundle,
-        bytes: &[u8], dimensions: (u32, u32),
-        label: &str
-        ) -> Result<Self> {
+        label: &str,
+        format: wgpu::TextureFormat, bytes: &[u8], dimensions: (u32, u32), bytes_per_pixel: u8,
+        usage: wgpu::TextureUsages, options: &TextureOptions) -> Result<Self> {
+
+        Self::from_layers(
+            bundle,
+            label,
+            format,
+            &[bytes],
+            dimensions,
+            bytes_per_pixel,
+            usage,
+            wgpu::TextureViewDimension::D2,
+            options,
+        )
+    }
 
-        let size = wgpu::Extent3d {
-            width: dimensions.0,
-            height: dimensions.1,
-            depth_or_array_layers: 1,
+    #[allow(clippy::too_many_arguments)]
+    fn from_layers(
+        bundle: &super::bundle::Bundle,
+        label: &str,
+        format: wgpu::TextureFormat, layers: &[&[u8]], dimensions: (u32, u32), bytes_per_pixel: u8,
+        usage: wgpu::TextureUsages, view_dimension: wgpu::TextureViewDimension,
+        options: &TextureOptions) -> Result<Self> {
+
+        let mipmaps = options.mipmaps;
+        let mip_level_count = match mipmaps {
+            Mipmaps::None => 1,
+            Mipmaps::Generate | Mipmaps::GenerateCpu => Mipmaps::level_count(dimensions),
+        };
+        let blit = mipmaps == Mipmaps::Generate && super::mipmaps::can_blit(format);
+        // Levels are generated before the texture is created, an unsupported format creates nothing.
+        let cpu_levels = if mip_level_count > 1 && !blit {
+            layers
+                .iter()
+                .map(|bytes| super::mipmaps::generate_cpu(format, bytes, dimensions, bytes_per_pixel))
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            Vec::new()
         };
-
-        let texture_format = wgpu::TextureFormat::R8Unorm;
 
         let texture = bundle.device().create_texture(
             &wgpu::TextureDescriptor {
                 label: Some(label),
-                size,
-                mip_level_count: 1,
+                size: wgpu::Extent3d {
+                    width: dimensions.0,
+                    height: dimensions.1,
+                    depth_or_array_layers: layers.len() as u32,
+                },
+                mip_level_count,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: texture_format,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                format,
+                usage: if blit { usage | wgpu::TextureUsages::RENDER_ATTACHMENT } else { usage },
             }
         );
 
+        for (layer, bytes) in layers.iter().enumerate() {
+            let layer_levels = cpu_levels.get(layer).map_or(&[][..], Vec::as_slice);
+            let levels = std::iter::once(*bytes).chain(layer_levels.iter().map(Vec::as_slice));
+            for (level, bytes) in levels.enumerate() {
+                Self::write_level(bundle, &texture, layer as u32, level as u32, bytes, dimensions, bytes_per_pixel);
+            }
+        }
+
+        if blit && mip_level_count > 1 {
+            for (layer, bytes) in layers.iter().enumerate() {
+                // A single layer of a texture can't be sampled on every backend,
+                // layers are blitted from a copy of their base level.
+                let copy;
+                let source = if layers.len() == 1 {
+                    texture.create_view(&wgpu::TextureViewDescriptor {
+                        mip_level_count: std::num::NonZeroU32::new(1),
+                        ..Default::default()
+                    })
+                } else {
+                    copy = bundle.device().create_texture(&wgpu::TextureDescriptor {
+                        label: Some("Layer Texture"),
+                        size: wgpu::Extent3d {
+                            width: dimensions.0,
+                            height: dimensions.1,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format,
+                        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                    });
+                    Self::write_level(bundle, &copy, 0, 0, bytes, dimensions, bytes_per_pixel);
+                    copy.create_view(&wgpu::TextureViewDescriptor::default())
+                };
+                super::mipmaps::generate_gpu(
+                    bundle,
+                    &source,
+                    &texture,
+                    layer as u32,
+                    format,
+                    dimensions,
+                    mip_level_count,
+                );
+            }
+        }
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(view_dimension),
+            ..Default::default()
+        });
+        // Textures with mip levels are sampled with trilinear filtering by default.
+        let sampler = options.sampler.unwrap_or_else(|| if mip_level_count > 1 {
+            SamplerOptions::trilinear()
+        } else {
+            SamplerOptions::default()
+        });
+        let sampler = bundle.sampler(&sampler);
+
+        Ok(Self { texture, view, sampler, view_dimension })
+    }
+
+    fn write_level(
+        bundle: &super::bundle::Bundle,
+        texture: &wgpu::Texture,
+        layer: u32, level: u32,
+        bytes: &[u8], dimensions: (u32, u32), bytes_per_pixel: u8,
+    ) {
+        let (width, height) = Mipmaps::level_dimensions(dimensions, level);
         bundle.queue().write_texture(
             wgpu::ImageCopyTexture {
                 aspect: wgpu::TextureAspect::All,
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
+                texture,
+                mip_level: level,
+                origin: wgpu::Origin3d { x: 0, y: 0, z: layer },
             },
-            &bytes,
+            bytes,
             wgpu::ImageDataLayout {
                 offset: 0,
-                bytes_per_row: std::num::NonZeroU32::new(dimensions.0),
-                rows_per_image: std::num::NonZeroU32::new(dimensions.1),
+                bytes_per_row: std::num::NonZeroU32::new(bytes_per_pixel as u32 * width),
+                rows_per_image: std::num::NonZeroU32::new(height),
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
             },
-            size,
         );
+    }
 
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = bundle.device().create_sampler(
-            &wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Nearest,
-                mipmap_filter: wgpu::FilterMode::Nearest,
-                ..Default::default()
-            }
-        );
-        
-        Ok(Self { texture, view, sampler })
+    pub fn bind_group_layout(bundle: &super::bundle::Bundle) -> wgpu::BindGroupLayout {
+        Self::bind_group_layout_with_dimension(bundle, wgpu::TextureViewDimension::D2)
     }
 
-    pub fn from_bytes_custom(
-        bundle: &super::bundle::Bundle,
-        label: &str,
-        format: wgpu::TextureFormat, bytes: &[u8], dimensions: (u32, u32), bytes_per_pixel: u8,
-        usage: wgpu::TextureUsages) -> Result<Self> {
+    /// Returns a layout of bind groups of cube textures, for skyboxes and reflection probes.
+    pub fn cube_bind_group_layout(bundle: &super::bundle::Bundle) -> wgpu::BindGroupLayout {
+        Self::bind_group_layout_with_dimension(bundle, wgpu::TextureViewDimension::Cube)
+    }
 
-            let size = wgpu::Extent3d {
-                width: dimensions.0,
-                height: dimensions.1,
-                depth_or_array_layers: 1,
-            };
-    
-            let texture_format = format;
-    
-            let texture = bundle.device().create_texture(
-                &wgpu::TextureDescriptor {
-                    label: Some(label),
-                    size,
-                    mip_level_count: 1,
-                    sample_count: 1,
-                    dimension: wgpu::TextureDimension::D2,
-                    format: texture_format,
-                    usage,
-                }
-            );
-    
-            bundle.queue().write_texture(
-                wgpu::ImageCopyTexture {
-                    aspect: wgpu::TextureAspect::All,
-                    texture: &texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                },
-                &bytes,
-                wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: std::num::NonZeroU32::new(bytes_per_pixel as u32 * dimensions.0),
-                    rows_per_image: std::num::NonZeroU32::new(dimensions.1),
-                },
-                size,
-            );
-    
-            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-            let sampler = bundle.device().create_sampler(
-                &wgpu::SamplerDescriptor {
-                    address_mode_u: wgpu::AddressMode::ClampToEdge,
-                    address_mode_v: wgpu::AddressMode::ClampToEdge,
-                    address_mode_w: wgpu::AddressMode::ClampToEdge,
-                    mag_filter: wgpu::FilterMode::Linear,
-                    min_filter: wgpu::FilterMode::Nearest,
-                    mipmap_filter: wgpu::FilterMode::Nearest,
-                    ..Default::default()
-                }
-            );
-            
-            Ok(Self { texture, view, sampler })
+    /// Returns a layout of bind groups of 2D texture arrays.
+    pub fn array_bind_group_layout(bundle: &super::bundle::Bundle) -> wgpu::BindGroupLayout {
+        Self::bind_group_layout_with_dimension(bundle, wgpu::TextureViewDimension::D2Array)
     }
 
-    pub fn bind_group_layout(bundle: &super::bundle::Bundle) -> wgpu::BindGroupLayout {
+    /// Returns a layout of bind groups of textures with the view dimension.
+    pub fn bind_group_layout_with_dimension(
+        bundle: &super::bundle::Bundle,
+        view_dimension: wgpu::TextureViewDimension,
+    ) -> wgpu::BindGroupLayout {
         bundle.device().create_bind_group_layout(
             &wgpu::BindGroupLayoutDescriptor {
                 entries: &[
@@ -260,7 +554,7 @@ impl Texture {
                         visibility: wgpu::ShaderStages::FRAGMENT,
                         ty: wgpu::BindingType::Texture {
                             multisampled: false,
-                            view_dimension: wgpu::TextureViewDimension::D2,
+                            view_dimension,
                             sample_type: wgpu::TextureSampleType::Float { filterable: true },
                         },
                         count: None,
@@ -277,9 +571,10 @@ impl Texture {
         )
     }
 
+    /// Creates a bind group with a layout matching its view dimension.
     pub fn bind_group(&self, bundle: &super::bundle::Bundle) -> wgpu::BindGroup {
         bundle.device().create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &Self::bind_group_layout(bundle),
+            layout: &Self::bind_group_layout_with_dimension(bundle, self.view_dimension),
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
@@ -315,40 +610,94 @@ impl Texture {
         bundle: &super::bundle::Bundle,
         format: wgpu::TextureFormat,
         label: &str
-    ) -> Self {    
+    ) -> Self {
+        Self::depth_texture_from_device(bundle.device(), bundle.config(), format, 1, label)
+    }
+
+    /// Creates a depth texture with the sample count, pass Bundle::sample_count to use it with the Bundle pipelines.
+    /// A multisampled depth texture is only a render attachment, it can't be bound.
+    pub fn depth_texture_multisampled(
+        bundle: &super::bundle::Bundle,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        label: &str
+    ) -> Self {
+        Self::depth_texture_from_device(bundle.device(), bundle.config(), format, sample_count, label)
+    }
+
+    pub(crate) fn depth_texture_from_device(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+        label: &str
+    ) -> Self {
         let size = wgpu::Extent3d {
-            width: bundle.config().width,
-            height: bundle.config().height,
+            width: config.width,
+            height: config.height,
             depth_or_array_layers: 1,
         };
         let desc = wgpu::TextureDescriptor {
             label: Some(label),
             size,
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
-            format: format,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                | wgpu::TextureUsages::TEXTURE_BINDING,
+            format,
+            // Multisampled depth textures can't be sampled by a comparison sampler, and the GL backend
+            // only creates a complete multisampled depth target if it is a render attachment only.
+            usage: if sample_count > 1 {
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+            } else {
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+            },
         };
-        let texture = bundle.device().create_texture(&desc);
+        let texture = device.create_texture(&desc);
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = bundle.device().create_sampler(
-            &wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Linear,
-                mipmap_filter: wgpu::FilterMode::Nearest,
-                compare: Some(wgpu::CompareFunction::LessEqual),
-                lod_min_clamp: -100.0,
-                lod_max_clamp: 100.0,
-                ..Default::default()
+        let sampler = SamplerOptions {
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            lod_min_clamp: -100.0,
+            lod_max_clamp: 100.0,
+            ..Default::default()
+        }
+        .create_sampler(device);
+
+        Self::new(texture, view, sampler)
+    }
+
+    /// Multisampled targets are only render attachments, they can't be copied.
+    pub(crate) fn render_target_from_device(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        sample_count: u32,
+        label: &str
+    ) -> Self {
+        let size = wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(
+            &wgpu::TextureDescriptor {
+                label: Some(label),
+                size,
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: config.format,
+                usage: if sample_count > 1 {
+                    wgpu::TextureUsages::RENDER_ATTACHMENT
+                } else {
+                    config.usage | wgpu::TextureUsages::TEXTURE_BINDING
+                },
             }
         );
 
-        Self { texture, view, sampler }
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = SamplerOptions::default().create_sampler(device);
+
+        Self::new(texture, view, sampler)
     }
 }
\ No newline at end of file
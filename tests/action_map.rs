@@ -0,0 +1,31 @@
+use heptagon::main_loop::{ActionMap, Axis2dBinding, AxisBinding, Binding, Key, MouseButton};
+
+#[test]
+fn bindings_round_trip_through_json() {
+    let mut action_map = ActionMap::new();
+    action_map.bind("jump", Binding::Key(Key::Space));
+    action_map.bind("jump", Binding::MouseButton(MouseButton::Right));
+    action_map.bind("save", Binding::Chord(vec![Key::LControl, Key::S]));
+    action_map.bind_axis("zoom", AxisBinding::new(Binding::Key(Key::Q), Binding::Key(Key::E)));
+    action_map.bind_axis_2d("move", Axis2dBinding::wasd());
+
+    let loaded = ActionMap::from_json(&action_map.to_json()).unwrap();
+
+    assert_eq!(loaded, action_map);
+    assert_eq!(loaded.bindings("jump").len(), 2);
+    assert_eq!(loaded.axis_2d_bindings("move"), &[Axis2dBinding::wasd()]);
+}
+
+#[test]
+fn unbind_removes_all_bindings() {
+    let mut action_map = ActionMap::new();
+    action_map.bind("fire", Binding::MouseButton(MouseButton::Left));
+    action_map.unbind("fire");
+
+    assert!(action_map.bindings("fire").is_empty());
+}
+
+#[test]
+fn invalid_json_is_an_error() {
+    assert!(ActionMap::from_json("{\"actions\": {\"jump\": [{\"Key\": \"NotAKey\"}]}}").is_err());
+}
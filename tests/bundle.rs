@@ -0,0 +1,132 @@
+use heptagon::rendering::bundle::{Bundle, BundleDescriptor};
+use heptagon::Error;
+
+fn descriptor() -> BundleDescriptor {
+    BundleDescriptor {
+        limits: wgpu::Limits::downlevel_defaults(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn linear_headless_format() {
+    let bundle = Bundle::headless_with_descriptor(
+        4,
+        4,
+        &BundleDescriptor {
+            srgb: false,
+            ..descriptor()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(bundle.config().format, wgpu::TextureFormat::Rgba8Unorm);
+    assert!(bundle.adapter_info().is_some());
+}
+
+#[test]
+fn missing_required_features_are_an_error() {
+    let result = Bundle::headless_with_descriptor(
+        4,
+        4,
+        &BundleDescriptor {
+            required_features: wgpu::Features::all(),
+            ..descriptor()
+        },
+    );
+
+    assert!(matches!(result, Err(Error::MissingFeatures(missing)) if !missing.is_empty()));
+}
+
+#[test]
+fn adapters_are_enumerated() {
+    let adapters = Bundle::adapters(wgpu::Backends::all());
+    let info = Bundle::headless(1, 1).unwrap().adapter_info().cloned().unwrap();
+
+    assert!(adapters.iter().any(|adapter| adapter.name == info.name));
+}
+
+#[test]
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn window_surfaces_can_be_read_back() {
+    use winit::platform::unix::EventLoopExtUnix;
+
+    if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        eprintln!("skipped, there is no display to create a window on");
+        return;
+    }
+
+    let event_loop = winit::event_loop::EventLoop::<()>::new_any_thread();
+    let window = std::sync::Arc::new(
+        winit::window::WindowBuilder::new()
+            .with_inner_size(winit::dpi::PhysicalSize::new(16, 16))
+            .with_visible(false)
+            .build(&event_loop)
+            .unwrap(),
+    );
+    let descriptor = BundleDescriptor {
+        readable_surface: true,
+        ..Default::default()
+    };
+    let mut bundle = match Bundle::with_descriptor(&window, &descriptor) {
+        Err(Error::NoAdapter) => return,
+        result => result.unwrap(),
+    };
+
+    let surface_texture = bundle.surface_texture().unwrap().unwrap();
+    let view = surface_texture
+        .texture()
+        .create_view(&wgpu::TextureViewDescriptor::default());
+    let mut encoder = bundle.encoder();
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: None,
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLUE),
+                store: true,
+            },
+        })],
+        depth_stencil_attachment: None,
+    });
+    bundle.queue().submit(std::iter::once(encoder.finish()));
+
+    let image = bundle.read_surface_texture(&surface_texture).unwrap();
+    surface_texture.present();
+    assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+}
+
+#[test]
+#[allow(deprecated)]
+fn surface_view_is_kept_for_headless_bundles() {
+    let mut bundle = Bundle::headless(4, 4).unwrap();
+
+    assert!(bundle.surface_view().is_some());
+}
+
+#[test]
+fn depth_textures_take_an_explicit_sample_count() {
+    use heptagon::rendering::Texture;
+
+    let mut bundle = Bundle::headless(4, 4).unwrap();
+    bundle.set_sample_count(4).unwrap();
+
+    let depth = Texture::depth_texture_multisampled(&bundle, Bundle::DEPTH_FORMAT, bundle.sample_count(), "depth");
+    let mut frame = bundle.begin_frame().unwrap().unwrap();
+    frame.render_pass([0.0, 0.0, 0.0, 1.0], depth.view());
+    frame.finish();
+
+    // Single sampled depth textures are used by offscreen passes without MSAA.
+    let depth = Texture::depth_texture(&bundle, Bundle::DEPTH_FORMAT, "depth");
+    let target = bundle.offscreen_texture().unwrap().view();
+    let mut encoder = bundle.encoder();
+    heptagon::rendering::RenderPass::begin(&mut encoder, target, [0.0; 4], depth.view());
+    bundle.queue().submit(std::iter::once(encoder.finish()));
+}
@@ -0,0 +1,119 @@
+//! Golden-image test support.
+//!
+//! Scenes are rendered with a headless Bundle and compared against reference
+//! PNGs stored in `tests/golden`. A missing reference fails the test; set
+//! `HEPTAGON_BLESS=1` to write new references or overwrite existing ones after
+//! an intended change in the rendering output.
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use heptagon::rendering::bundle::Bundle;
+
+/// Width of rendered scenes.
+pub const WIDTH: u32 = 128;
+/// Height of rendered scenes.
+pub const HEIGHT: u32 = 128;
+/// The default per-channel tolerance, covers rasterization differences between adapters.
+pub const TOLERANCE: u8 = 8;
+
+/// Creates a headless Bundle of the scene size.
+pub fn bundle() -> Bundle {
+    Bundle::headless(WIDTH, HEIGHT).unwrap()
+}
+
+/// Result of comparing an image with its reference.
+pub struct Comparison {
+    /// Number of pixels which differ by more than the tolerance.
+    pub mismatched: u32,
+    /// The largest per-channel difference.
+    pub max_difference: u8,
+    /// Image highlighting mismatched pixels in red.
+    pub diff: image::RgbaImage,
+}
+
+/// Compares two images of the same size channel by channel.
+pub fn compare(actual: &image::RgbaImage, reference: &image::RgbaImage, tolerance: u8) -> Comparison {
+    let mut mismatched = 0;
+    let mut max_difference = 0;
+    let mut diff = image::RgbaImage::new(actual.width(), actual.height());
+
+    for (x, y, pixel) in actual.enumerate_pixels() {
+        let expected = reference.get_pixel(x, y);
+        let difference = pixel
+            .0
+            .iter()
+            .zip(expected.0.iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+
+        if difference > tolerance {
+            mismatched += 1;
+            diff.put_pixel(x, y, image::Rgba([255, 0, 0, 255]));
+        } else {
+            let luma = (pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3 / 4;
+            diff.put_pixel(x, y, image::Rgba([luma as u8, luma as u8, luma as u8, 255]));
+        }
+    }
+
+    Comparison {
+        mismatched,
+        max_difference,
+        diff,
+    }
+}
+
+/// Reads the current frame of the Bundle and compares it with the reference `tests/golden/<name>.png`.
+/// Writes the actual and diff images to the cargo target directory on failure.
+/// The reference is only written if HEPTAGON_BLESS is set, a missing reference fails.
+pub fn assert_golden(bundle: &mut Bundle, name: &str, tolerance: u8) {
+    let surface_texture = bundle.surface_texture().unwrap().unwrap();
+    let actual = bundle.read_surface_texture(&surface_texture).unwrap();
+
+    let reference_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{}.png", name));
+
+    if std::env::var_os("HEPTAGON_BLESS").is_some() {
+        actual.save(&reference_path).unwrap();
+        return;
+    }
+    assert!(
+        reference_path.exists(),
+        "{}: reference {} is missing, run the tests with HEPTAGON_BLESS=1 to write it",
+        name,
+        reference_path.display()
+    );
+
+    let reference = image::open(&reference_path).unwrap().to_rgba8();
+    assert_eq!(
+        actual.dimensions(),
+        reference.dimensions(),
+        "{}: rendered image has different dimensions than the reference",
+        name
+    );
+
+    let comparison = compare(&actual, &reference, tolerance);
+    if comparison.mismatched > 0 {
+        let output_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        std::fs::create_dir_all(&output_dir).unwrap();
+        let actual_path = output_dir.join(format!("{}.actual.png", name));
+        let diff_path = output_dir.join(format!("{}.diff.png", name));
+        actual.save(&actual_path).unwrap();
+        comparison.diff.save(&diff_path).unwrap();
+
+        panic!(
+            "{}: {} pixels differ from the reference (max difference {}, tolerance {}), see {} and {}",
+            name,
+            comparison.mismatched,
+            comparison.max_difference,
+            tolerance,
+            actual_path.display(),
+            diff_path.display(),
+        );
+    }
+}
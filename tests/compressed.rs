@@ -0,0 +1,248 @@
+use heptagon::rendering::*;
+
+const RED: [u8; 4] = [255, 0, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+/// A BC1 block between red and blue, every row has the indices 0, 1, 2 and 3.
+const BC1_BLOCK: [u8; 8] = [0x00, 0xf8, 0x1f, 0x00, 0xe4, 0xe4, 0xe4, 0xe4];
+
+fn dds(format: ddsfile::DxgiFormat, dimensions: (u32, u32), levels: u32, block: &[u8]) -> Vec<u8> {
+    let mut dds = ddsfile::Dds::new_dxgi(ddsfile::NewDxgiParams {
+        height: dimensions.1,
+        width: dimensions.0,
+        depth: None,
+        format,
+        mipmap_levels: Some(levels),
+        array_layers: None,
+        caps2: None,
+        is_cubemap: false,
+        resource_dimension: ddsfile::D3D10ResourceDimension::Texture2D,
+        alpha_mode: ddsfile::AlphaMode::Unknown,
+    })
+    .unwrap();
+    for chunk in dds.data.chunks_mut(block.len()) {
+        chunk.copy_from_slice(block);
+    }
+
+    let mut bytes = Vec::new();
+    dds.write(&mut bytes).unwrap();
+    bytes
+}
+
+/// Writes a KTX2 container without data format descriptor or key/value data.
+fn ktx2(format: u32, dimensions: (u32, u32), layers: u32, faces: u32, levels: &[Vec<u8>]) -> Vec<u8> {
+    let mut bytes = vec![0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a];
+    for field in [format, 1, dimensions.0, dimensions.1, 0, layers, faces, levels.len() as u32, 0, 0, 0, 0, 0] {
+        bytes.extend_from_slice(&field.to_le_bytes());
+    }
+    bytes.extend_from_slice(&[0; 16]);
+
+    let mut offset = 80 + levels.len() * 24;
+    for level in levels {
+        for field in [offset, level.len(), level.len()] {
+            bytes.extend_from_slice(&(field as u64).to_le_bytes());
+        }
+        offset += level.len();
+    }
+    for level in levels {
+        bytes.extend_from_slice(level);
+    }
+    bytes
+}
+
+fn texel(bytes: &[u8], width: usize, x: usize, y: usize) -> [u8; 4] {
+    bytes[(y * width + x) * 4..][..4].try_into().unwrap()
+}
+
+#[test]
+fn dds_bc1_levels_are_decompressed() {
+    let bytes = dds(ddsfile::DxgiFormat::BC1_UNorm, (8, 8), 4, &BC1_BLOCK);
+    let container = TextureContainer::from_dds(&bytes).unwrap();
+    assert_eq!(container.format, wgpu::TextureFormat::Bc1RgbaUnorm);
+    assert_eq!(container.view_dimension, wgpu::TextureViewDimension::D2);
+    assert_eq!(container.level_count(), 4);
+    assert_eq!(container.layer(0, 0).len(), 32);
+
+    let decompressed = container.decompress().unwrap();
+    assert_eq!(decompressed.format, wgpu::TextureFormat::Rgba8Unorm);
+    assert_eq!(decompressed.levels[0].len(), 8 * 8 * 4);
+    assert_eq!(texel(&decompressed.levels[0], 8, 0, 0), RED);
+    assert_eq!(texel(&decompressed.levels[0], 8, 5, 7), BLUE);
+    assert_eq!(texel(&decompressed.levels[0], 8, 2, 1), [170, 0, 85, 255]);
+    assert_eq!(texel(&decompressed.levels[0], 8, 7, 4), [85, 0, 170, 255]);
+    // Levels smaller than a block keep their top left texels.
+    assert_eq!(decompressed.levels[2], [RED, BLUE, RED, BLUE].concat());
+}
+
+#[test]
+fn dds_bc4_values_are_interpolated() {
+    // Indices 0 to 7 twice, three bits each.
+    let indices = (0..16u64).fold(0, |bits, i| bits | (i % 8) << (3 * i));
+    let mut block = vec![255, 0];
+    block.extend_from_slice(&indices.to_le_bytes()[..6]);
+
+    let bytes = dds(ddsfile::DxgiFormat::BC4_UNorm, (4, 4), 1, &block);
+    let decompressed = TextureContainer::from_dds(&bytes).unwrap().decompress().unwrap();
+    assert_eq!(decompressed.format, wgpu::TextureFormat::R8Unorm);
+    assert_eq!(decompressed.levels[0][..8], [255, 0, 218, 182, 145, 109, 72, 36]);
+}
+
+#[test]
+fn dds_bc5_snorm_values_are_signed() {
+    // Red from 1.0 to -1.0 with -128 read as -127, green has six values with -1.0 and 1.0 in the first texels.
+    let indices = (0..16u64).fold(0, |bits, i| bits | (i % 8) << (3 * i));
+    let mut block = vec![127, 0x80];
+    block.extend_from_slice(&indices.to_le_bytes()[..6]);
+    block.extend_from_slice(&[0, 0, 0b111_110, 0, 0, 0, 0, 0]);
+
+    let bytes = dds(ddsfile::DxgiFormat::BC5_SNorm, (4, 4), 1, &block);
+    let decompressed = TextureContainer::from_dds(&bytes).unwrap().decompress().unwrap();
+    assert_eq!(decompressed.format, wgpu::TextureFormat::Rg8Snorm);
+    let values = decompressed.levels[0].iter().map(|&byte| byte as i8).collect::<Vec<_>>();
+    assert_eq!(values[..8], [127, -127, -127, 127, 90, 0, 54, 0]);
+    assert_eq!(values[8..16], [18, 0, -18, 0, -54, 0, -90, 0]);
+}
+
+/// Writes a one region BC6H block from the mode and header fields with their number of bits,
+/// the anchor texel has index 0 and the others the index.
+fn bc6h_block(mode: u128, header: &[(u128, u32)], index: u128) -> Vec<u8> {
+    let mut bits = mode;
+    let mut position = 5;
+    for &(value, count) in header {
+        bits |= value << position;
+        position += count;
+    }
+    for texel in 1..16 {
+        bits |= index << (65 + 3 + 4 * (texel - 1));
+    }
+    bits.to_le_bytes().to_vec()
+}
+
+fn half_texel(bytes: &[u8], x: usize) -> [u16; 4] {
+    let texel = &bytes[x * 8..][..8];
+    [0, 1, 2, 3].map(|channel| u16::from_le_bytes([texel[channel * 2], texel[channel * 2 + 1]]))
+}
+
+#[test]
+fn ktx2_bc6h_is_decompressed_to_half_floats() {
+    let block = bc6h_block(0b00011, &[(0, 30), (1023, 10), (512, 10), (0, 10)], 15);
+    let bytes = ktx2(143, (4, 4), 0, 1, &[block]);
+    let container = TextureContainer::from_bytes(&bytes).unwrap();
+    assert_eq!(container.format, wgpu::TextureFormat::Bc6hRgbUfloat);
+
+    let decompressed = container.decompress().unwrap();
+    assert_eq!(decompressed.format, wgpu::TextureFormat::Rgba16Float);
+    assert_eq!(decompressed.levels[0].len(), 4 * 4 * 8);
+    assert_eq!(half_texel(&decompressed.levels[0], 0), [0, 0, 0, 0x3c00]);
+    // 65504 is the largest half float, 512 of 1023 is about 1.5 as in the BC6H unquantization.
+    assert_eq!(half_texel(&decompressed.levels[0], 1), [0x7bff, 0x3e0f, 0, 0x3c00]);
+
+    // Signed endpoints are sign extended, 0x201 is the smallest 10 bit value.
+    let block = bc6h_block(0b00011, &[(0, 30), (0x201, 10), (0, 10), (0x1ff, 10)], 15);
+    let bytes = ktx2(144, (4, 4), 0, 1, &[block]);
+    let decompressed = TextureContainer::from_bytes(&bytes).unwrap().decompress().unwrap();
+    assert_eq!(half_texel(&decompressed.levels[0], 15), [0xfbff, 0, 0x7bff, 0x3c00]);
+
+    // Mode 12 stores the second endpoint as 9 bit deltas and the eleventh bit of the first one after them.
+    let header = [(0, 30), (0x1ff, 9), (1, 1), (0x100, 9), (0, 1), (0, 9), (0, 1)];
+    let bytes = ktx2(143, (4, 4), 0, 1, &[bc6h_block(0b00111, &header, 15)]);
+    let decompressed = TextureContainer::from_bytes(&bytes).unwrap().decompress().unwrap();
+    assert_eq!(half_texel(&decompressed.levels[0], 0), [0x3e07, 0, 0, 0x3c00]);
+    assert_eq!(half_texel(&decompressed.levels[0], 1), [0x3df8, 0x6c87, 0, 0x3c00]);
+}
+
+#[test]
+fn ktx2_bc7_mode_6_is_decompressed() {
+    // Mode 6 from red 1 to 255 with opaque alpha, texels have indices of their position.
+    let mut bits = 1u128 << 6;
+    let mut position = 7;
+    let mut write = |value: u128, count: u32| {
+        bits |= value << position;
+        position += count;
+    };
+    for value in [0, 127, 0, 0, 0, 0, 127, 127] {
+        write(value, 7);
+    }
+    write(1, 1);
+    write(1, 1);
+    write(0, 3);
+    for index in 1..16 {
+        write(index, 4);
+    }
+
+    let bytes = ktx2(145, (4, 4), 0, 1, &[bits.to_le_bytes().to_vec()]);
+    let container = TextureContainer::from_bytes(&bytes).unwrap();
+    assert_eq!(container.format, wgpu::TextureFormat::Bc7RgbaUnorm);
+
+    let decompressed = container.decompress().unwrap();
+    assert_eq!(texel(&decompressed.levels[0], 4, 0, 0), [1, 1, 1, 255]);
+    assert_eq!(texel(&decompressed.levels[0], 4, 0, 2), [136, 1, 1, 255]);
+    assert_eq!(texel(&decompressed.levels[0], 4, 3, 3), [255, 1, 1, 255]);
+}
+
+#[test]
+fn ktx2_layers_and_levels_are_uploaded() {
+    let colors = [RED, BLUE, [0, 255, 0, 255], [255, 255, 0, 255], [0, 255, 255, 255], [255, 0, 255, 255]];
+    let levels = |layers: usize| -> Vec<Vec<u8>> {
+        [16, 4, 1]
+            .iter()
+            .map(|&texels| colors[..layers].iter().flat_map(|color| color.repeat(texels)).collect())
+            .collect()
+    };
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+
+    let bytes = ktx2(37, (4, 4), 3, 1, &levels(3));
+    let array = Texture::from_ktx2(&bundle, &bytes, &TextureOptions::default(), "array").unwrap();
+    assert_eq!(array.view_dimension(), wgpu::TextureViewDimension::D2Array);
+    for layer in 0..3 {
+        let image = bundle
+            .read_texture_layer_level(array.texture(), wgpu::TextureFormat::Rgba8Unorm, (1, 1), layer, 2)
+            .unwrap();
+        assert_eq!(image.get_pixel(0, 0).0, colors[layer as usize], "{}", layer);
+    }
+
+    let bytes = ktx2(37, (4, 4), 0, 6, &levels(6));
+    let container = TextureContainer::from_ktx2(&bytes).unwrap();
+    assert_eq!(container.view_dimension, wgpu::TextureViewDimension::Cube);
+    assert_eq!(container.layer(1, CubeFace::NegativeX as u32), BLUE.repeat(4));
+    let cube = Texture::from_container(&bundle, &container, &TextureOptions::default(), "cube").unwrap();
+    cube.bind_group_with_layout(&bundle, &Texture::cube_bind_group_layout(&bundle));
+}
+
+#[test]
+fn compressed_textures_fall_back_to_decompression() {
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let bytes = dds(ddsfile::DxgiFormat::BC1_UNorm, (8, 8), 4, &BC1_BLOCK);
+    let texture = Texture::from_dds(&bundle, &bytes, &TextureOptions::default(), "bc1").unwrap();
+    texture.bind_group(&bundle);
+
+    if !bundle.features().contains(wgpu::Features::TEXTURE_COMPRESSION_BC) {
+        let image = bundle
+            .read_texture_level(texture.texture(), wgpu::TextureFormat::Rgba8Unorm, (4, 4), 1)
+            .unwrap();
+        assert_eq!(image.get_pixel(0, 3).0, RED);
+        assert_eq!(image.get_pixel(3, 0).0, [85, 0, 170, 255]);
+    }
+}
+
+#[test]
+fn invalid_containers_are_rejected() {
+    assert!(matches!(
+        TextureContainer::from_bytes(b"not a texture"),
+        Err(heptagon::Error::UnsupportedContainer(_))
+    ));
+
+    let mut bytes = dds(ddsfile::DxgiFormat::BC1_UNorm, (8, 8), 4, &BC1_BLOCK);
+    bytes.truncate(bytes.len() - 8);
+    assert!(matches!(TextureContainer::from_dds(&bytes), Err(heptagon::Error::UnsupportedContainer(_))));
+
+    assert!(matches!(TextureContainer::from_ktx2(&[0; 8]), Err(heptagon::Error::Ktx2Parse(_))));
+
+    // ETC2 is only uploaded when the device supports it.
+    let etc2 = ktx2(147, (4, 4), 0, 1, &[vec![0; 8]]);
+    let container = TextureContainer::from_ktx2(&etc2).unwrap();
+    assert!(matches!(
+        container.decompress(),
+        Err(heptagon::Error::UnsupportedFormat(wgpu::TextureFormat::Etc2Rgb8Unorm))
+    ));
+}
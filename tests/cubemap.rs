@@ -0,0 +1,128 @@
+use heptagon::rendering::*;
+
+const COLORS: [[u8; 4]; 6] = [
+    [255, 0, 0, 255],
+    [0, 255, 0, 255],
+    [0, 0, 255, 255],
+    [255, 255, 0, 255],
+    [0, 255, 255, 255],
+    [255, 0, 255, 255],
+];
+
+fn solid(color: [u8; 4], size: (u32, u32)) -> image::DynamicImage {
+    image::DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(size.0, size.1, image::Rgba(color)))
+}
+
+#[test]
+fn face_directions_point_out_of_the_cube() {
+    assert_eq!(CubeFace::PositiveX.direction(0.0, 0.0), glam::Vec3::X);
+    assert_eq!(CubeFace::NegativeY.direction(0.0, 0.0), -glam::Vec3::Y);
+    assert_eq!(CubeFace::NegativeZ.direction(0.0, 0.0), -glam::Vec3::Z);
+    // The top of side faces is +Y.
+    assert!(CubeFace::PositiveZ.direction(0.0, -1.0).y > 0.0);
+}
+
+#[test]
+fn equirectangular_faces_follow_longitude() {
+    // Columns around longitudes of +Z, -X, -Z and +X.
+    let columns = [4, 1, 1, 5, 5, 0, 0, 4];
+    let panorama = image::RgbaImage::from_fn(8, 4, |x, _| image::Rgba(COLORS[columns[x as usize]]));
+
+    let faces = equirectangular_to_cube_faces(&panorama, 5).unwrap();
+    assert_eq!(faces.len(), 6);
+    for face in [CubeFace::PositiveX, CubeFace::NegativeX, CubeFace::PositiveZ, CubeFace::NegativeZ] {
+        assert_eq!(faces[face as usize].get_pixel(2, 2).0, COLORS[face as usize], "{:?}", face);
+    }
+}
+
+#[test]
+fn empty_cube_faces_are_rejected() {
+    let panorama = image::RgbaImage::from_pixel(8, 4, image::Rgba(COLORS[0]));
+    assert!(matches!(
+        equirectangular_to_cube_faces(&panorama, 0),
+        Err(heptagon::Error::InvalidDimensions(_))
+    ));
+
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let panorama = image::DynamicImage::ImageRgba8(panorama);
+    assert!(matches!(
+        Texture::from_equirectangular(&bundle, &panorama, 0, &TextureOptions::default(), "sky"),
+        Err(heptagon::Error::InvalidDimensions(_))
+    ));
+}
+
+#[test]
+fn empty_panoramas_are_rejected() {
+    let panorama = image::RgbaImage::new(0, 0);
+    assert!(matches!(
+        equirectangular_to_cube_faces(&panorama, 4),
+        Err(heptagon::Error::InvalidDimensions(_))
+    ));
+
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let panorama = image::DynamicImage::ImageRgba8(panorama);
+    assert!(matches!(
+        Texture::from_equirectangular(&bundle, &panorama, 4, &TextureOptions::default(), "sky"),
+        Err(heptagon::Error::InvalidDimensions(_))
+    ));
+}
+
+#[test]
+fn cube_faces_must_be_square_and_equal() {
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let options = TextureOptions::default();
+
+    let mut faces = COLORS.map(|color| solid(color, (4, 4)));
+    faces[3] = solid(COLORS[3], (2, 2));
+    assert!(matches!(
+        Texture::from_cube_images(&bundle, &faces, &options, "cube"),
+        Err(heptagon::Error::ImageSizeMismatch { expected: (4, 4), found: (2, 2) })
+    ));
+
+    let faces = COLORS.map(|color| solid(color, (4, 2)));
+    assert!(Texture::from_cube_images(&bundle, &faces, &options, "cube").is_err());
+    assert!(matches!(
+        Texture::from_image_array(&bundle, &[], &options, "array"),
+        Err(heptagon::Error::EmptyTextureArray)
+    ));
+}
+
+#[test]
+fn cube_and_array_bind_groups() {
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let options = TextureOptions {
+        mipmaps: Mipmaps::Generate,
+        ..Default::default()
+    };
+
+    let cube = Texture::from_cube_images(&bundle, &COLORS.map(|color| solid(color, (4, 4))), &options, "cube").unwrap();
+    assert_eq!(cube.view_dimension(), wgpu::TextureViewDimension::Cube);
+    cube.bind_group(&bundle);
+    cube.bind_group_with_layout(&bundle, &Texture::cube_bind_group_layout(&bundle));
+
+    let layers: Vec<_> = COLORS[..3].iter().map(|&color| solid(color, (8, 8))).collect();
+    let array = Texture::from_image_array(&bundle, &layers, &options, "array").unwrap();
+    assert_eq!(array.view_dimension(), wgpu::TextureViewDimension::D2Array);
+    array.bind_group_with_layout(&bundle, &Texture::array_bind_group_layout(&bundle));
+}
+
+#[test]
+fn mip_levels_of_every_layer_are_generated() {
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let images: Vec<_> = COLORS[..3].iter().map(|&color| solid(color, (8, 8))).collect();
+
+    for mipmaps in [Mipmaps::Generate, Mipmaps::GenerateCpu] {
+        let options = TextureOptions {
+            mipmaps,
+            ..Default::default()
+        };
+        let array = Texture::from_image_array(&bundle, &images, &options, "array").unwrap();
+
+        for layer in 0..3 {
+            let image = bundle
+                .read_texture_layer_level(array.texture(), wgpu::TextureFormat::Rgba8UnormSrgb, (1, 1), layer, 3)
+                .unwrap();
+            assert_eq!(image.get_pixel(0, 0).0, COLORS[layer as usize], "{:?} {}", mipmaps, layer);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::error::Error as _;
+
+use heptagon::rendering::Font;
+use heptagon::Error;
+
+#[test]
+fn missing_files_keep_their_path() {
+    let result = Font::from_path("tests/does-not-exist.ttf");
+
+    match result {
+        Err(Error::MissingFile { path, source }) => {
+            assert_eq!(path, std::path::Path::new("tests/does-not-exist.ttf"));
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        _ => panic!("expected Error::MissingFile"),
+    }
+}
+
+#[test]
+fn garbage_font_bytes_are_a_parse_error() {
+    let error = Font::from_bytes(b"not a font".to_vec()).err().unwrap();
+
+    assert!(matches!(error, Error::FontParse(_)));
+    assert!(error.to_string().starts_with("failed to parse font"));
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn surface_out_of_memory_is_an_error() {
+    let error = Error::SurfaceOutOfMemory;
+
+    assert_eq!(error.to_string(), "out of memory while acquiring a surface texture");
+    assert!(error.source().is_none());
+}
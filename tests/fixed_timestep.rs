@@ -0,0 +1,20 @@
+use heptagon::main_loop::FixedTimestep;
+
+#[test]
+fn advance_runs_whole_steps_and_keeps_remainder() {
+    let mut fixed_timestep = FixedTimestep::new(10.0);
+
+    assert_eq!(fixed_timestep.advance(0.05), 0);
+    assert_eq!(fixed_timestep.advance(0.1), 1);
+    assert!((fixed_timestep.alpha() - 0.5).abs() < 1e-4);
+}
+
+#[test]
+fn advance_caps_catch_up_steps() {
+    let mut fixed_timestep = FixedTimestep::new(10.0);
+    fixed_timestep.set_max_steps(3);
+
+    assert_eq!(fixed_timestep.advance(1.05), 3);
+    assert!(fixed_timestep.alpha() < 1.0);
+    assert_eq!(fixed_timestep.advance(0.0), 0);
+}
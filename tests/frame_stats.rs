@@ -0,0 +1,27 @@
+use heptagon::main_loop::FrameStats;
+
+#[test]
+fn statistics_of_recent_frames() {
+    let mut stats = FrameStats::new(4);
+    for frame_time in [0.5, 0.010, 0.020, 0.030, 0.040] {
+        stats.add_frame(frame_time);
+    }
+
+    assert_eq!(stats.frame_count(), 5);
+    assert_eq!(stats.delta(), 0.040);
+    assert_eq!(stats.min(), 0.010);
+    assert_eq!(stats.max(), 0.040);
+    assert!((stats.average() - 0.025).abs() < 1e-6);
+    assert!((stats.fps() - 40.0).abs() < 1e-3);
+    assert_eq!(stats.percentile(50.0), 0.020);
+    assert_eq!(stats.percentile(100.0), 0.040);
+}
+
+#[test]
+fn empty_statistics_are_zero() {
+    let stats = FrameStats::default();
+
+    assert_eq!(stats.delta(), 0.0);
+    assert_eq!(stats.fps(), 0.0);
+    assert_eq!(stats.percentile(99.0), 0.0);
+}
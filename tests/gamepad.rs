@@ -0,0 +1,128 @@
+use heptagon::main_loop::{
+    ActionMap, Axis2dBinding, Binding, GamepadAxis, GamepadButton, GamepadEvent, Input, MockGamepadBackend,
+    RecordedEvent,
+};
+
+fn input_with_mock() -> (Input, MockGamepadBackend) {
+    let backend = MockGamepadBackend::new();
+    let mut input = Input::new();
+    input.set_gamepad_backend(Box::new(backend.clone()));
+    (input, backend)
+}
+
+#[test]
+fn connect_and_disconnect() {
+    let (mut input, backend) = input_with_mock();
+
+    input.begin_frame();
+    backend.connect(3, "Pad");
+    input.end_frame();
+    assert!(input.gamepads().is_connected(3));
+    assert_eq!(input.gamepads().name(3), Some("Pad"));
+    assert_eq!(input.gamepads().just_connected().collect::<Vec<_>>(), vec![3]);
+
+    input.begin_frame();
+    input.end_frame();
+    assert_eq!(input.gamepads().just_connected().count(), 0);
+
+    input.begin_frame();
+    backend.disconnect(3);
+    input.end_frame();
+    assert!(!input.gamepads().is_connected(3));
+    assert_eq!(input.gamepads().just_disconnected().collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn buttons_are_pressed_held_and_released() {
+    let (mut input, backend) = input_with_mock();
+    backend.connect(0, "Pad");
+
+    input.begin_frame();
+    backend.press(0, GamepadButton::South);
+    input.end_frame();
+    assert!(input.gamepads().button_pressed(0, GamepadButton::South));
+    assert!(input.gamepads().button_held(0, GamepadButton::South));
+
+    input.begin_frame();
+    input.end_frame();
+    assert!(!input.gamepads().button_pressed(0, GamepadButton::South));
+    assert!(input.gamepads().button_held(0, GamepadButton::South));
+
+    input.begin_frame();
+    backend.release(0, GamepadButton::South);
+    input.end_frame();
+    assert!(input.gamepads().button_released(0, GamepadButton::South));
+    assert!(!input.gamepads().button_held(0, GamepadButton::South));
+}
+
+#[test]
+fn dead_zone_is_applied_and_rescaled() {
+    let (mut input, backend) = input_with_mock();
+    input.gamepads_mut().set_dead_zone(0.2);
+
+    input.begin_frame();
+    backend.connect(0, "Pad");
+    backend.set_axis(0, GamepadAxis::LeftStickX, 0.1);
+    backend.set_axis(0, GamepadAxis::LeftStickY, 0.0);
+    backend.set_axis(0, GamepadAxis::RightTrigger, 0.6);
+    input.end_frame();
+
+    let gamepads = input.gamepads();
+    assert_eq!(gamepads.axis(0, GamepadAxis::LeftStickX), 0.0);
+    assert_eq!(gamepads.raw_axis(0, GamepadAxis::LeftStickX), 0.1);
+    assert_eq!(gamepads.left_stick(0), glam::Vec2::ZERO);
+    assert!((gamepads.axis(0, GamepadAxis::RightTrigger) - 0.5).abs() < 1e-5);
+
+    input.begin_frame();
+    backend.set_axis(0, GamepadAxis::LeftStickX, 0.6);
+    backend.set_axis(0, GamepadAxis::LeftStickY, 0.8);
+    input.end_frame();
+
+    let stick = input.gamepads().left_stick(0);
+    assert!((stick.length() - 1.0).abs() < 1e-5);
+    assert!((stick.x / stick.y - 0.75).abs() < 1e-5);
+}
+
+#[test]
+fn action_map_reads_gamepads() {
+    let mut action_map = ActionMap::new();
+    action_map.bind("jump", Binding::GamepadButton(GamepadButton::South));
+    action_map.bind_axis_2d("move", Axis2dBinding::left_stick());
+    action_map.bind(
+        "accelerate",
+        Binding::GamepadAxis {
+            axis: GamepadAxis::RightTrigger,
+            positive: true,
+        },
+    );
+
+    let (mut input, backend) = input_with_mock();
+    input.begin_frame();
+    backend.connect(1, "Pad");
+    backend.press(1, GamepadButton::South);
+    backend.set_axis(1, GamepadAxis::LeftStickX, -1.0);
+    backend.set_axis(1, GamepadAxis::RightTrigger, 0.9);
+    input.end_frame();
+
+    assert!(action_map.action_pressed(&input, "jump"));
+    assert!(action_map.action_pressed(&input, "accelerate"));
+    assert_eq!(action_map.axis_2d(&input, "move"), glam::vec2(-1.0, 0.0));
+
+    input.begin_frame();
+    backend.set_axis(1, GamepadAxis::RightTrigger, 0.0);
+    input.end_frame();
+    assert!(action_map.action_released(&input, "accelerate"));
+    assert!(action_map.action_held(&input, "jump"));
+}
+
+#[test]
+fn gamepad_events_are_injected() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.inject(&RecordedEvent::Gamepad(GamepadEvent::ButtonPressed(0, GamepadButton::Start)));
+    input.end_frame();
+
+    assert!(input.gamepads().button_pressed(0, GamepadButton::Start));
+    assert!(RecordedEvent::Gamepad(GamepadEvent::Disconnected(0)).to_event().is_none());
+}
@@ -0,0 +1,231 @@
+mod common;
+
+use heptagon::rendering::*;
+
+const CLEAR_COLOR: [f64; 4] = [0.1, 0.2, 0.3, 1.0];
+/// CLEAR_COLOR as it is read back from the sRGB target.
+const CLEAR_COLOR_SRGB: [u8; 4] = [89, 124, 149, 255];
+
+fn quad(size: f32) -> Mesh<TextureVertex, u16> {
+    let vertices = Vertices::new(vec![
+        TextureVertex::new(glam::vec3(-size, -size, 0.0), glam::vec2(0.0, 1.0)),
+        TextureVertex::new(glam::vec3(size, -size, 0.0), glam::vec2(1.0, 1.0)),
+        TextureVertex::new(glam::vec3(size, size, 0.0), glam::vec2(1.0, 0.0)),
+        TextureVertex::new(glam::vec3(-size, size, 0.0), glam::vec2(0.0, 0.0)),
+    ]);
+    let indices = Indices::new(vec![0u16, 1, 2, 0, 2, 3]);
+
+    Mesh::new(vertices, indices)
+}
+
+fn checker_image() -> image::DynamicImage {
+    let image = image::RgbaImage::from_fn(4, 4, |x, y| {
+        if (x + y) % 2 == 0 {
+            image::Rgba([255, 255, 255, 255])
+        } else {
+            image::Rgba([200, 30, 30, 255])
+        }
+    });
+
+    image::DynamicImage::ImageRgba8(image)
+}
+
+fn submit_pass(
+    bundle: &mut bundle::Bundle,
+    draw: impl FnOnce(&mut Frame, &wgpu::TextureView),
+) {
+    let mut frame = bundle.begin_frame().unwrap().unwrap();
+    draw(&mut frame, bundle.depth_texture().view());
+    frame.finish();
+}
+
+#[test]
+fn texture_pipeline() {
+    let mut bundle = common::bundle();
+    let pipeline = bundle.texture_pipeline();
+
+    let mesh = quad(0.5);
+    let mesh_buffer = mesh.mesh_buffer(&bundle);
+    let texture = Texture::from_image(&bundle, &checker_image(), "checker").unwrap();
+    let texture_bind_group = texture.bind_group(&bundle);
+    let mvp = Uniform::new(glam::Mat4::from_rotation_z(0.3));
+    let mvp_bind_group = mvp.bind_group(&bundle);
+
+    submit_pass(&mut bundle, |frame, depth| {
+        let mut pass = frame.render_pass(CLEAR_COLOR, depth);
+        pass.render_texture(
+            mesh_buffer.vertex_buffer_slice(),
+            mesh_buffer.index_buffer_slice(),
+            0..mesh_buffer.index_count(),
+            &texture_bind_group,
+            &mvp_bind_group,
+            &pipeline,
+        );
+    });
+
+    common::assert_golden(&mut bundle, "texture_pipeline", common::TOLERANCE);
+}
+
+#[test]
+fn texture_pipeline_msaa() {
+    let mut bundle = common::bundle();
+    bundle.set_sample_count(4).unwrap();
+    assert!(bundle.msaa_texture().is_some());
+    let pipeline = bundle.texture_pipeline();
+
+    let mesh = quad(0.5);
+    let mesh_buffer = mesh.mesh_buffer(&bundle);
+    let texture = Texture::from_image(&bundle, &checker_image(), "checker").unwrap();
+    let texture_bind_group = texture.bind_group(&bundle);
+    let mvp = Uniform::new(glam::Mat4::from_rotation_z(0.3));
+    let mvp_bind_group = mvp.bind_group(&bundle);
+
+    submit_pass(&mut bundle, |frame, depth| {
+        let mut pass = frame.render_pass(CLEAR_COLOR, depth);
+        pass.render_texture(
+            mesh_buffer.vertex_buffer_slice(),
+            mesh_buffer.index_buffer_slice(),
+            0..mesh_buffer.index_count(),
+            &texture_bind_group,
+            &mvp_bind_group,
+            &pipeline,
+        );
+    });
+
+    // A failed resolve leaves the target transparent, which must not be blessed as a reference.
+    let surface_texture = bundle.surface_texture().unwrap().unwrap();
+    let image = bundle.read_surface_texture(&surface_texture).unwrap();
+    let is_clear = |pixel: &image::Rgba<u8>| {
+        pixel.0.iter().zip(CLEAR_COLOR_SRGB).all(|(a, b)| a.abs_diff(b) <= common::TOLERANCE)
+    };
+    assert!(is_clear(image.get_pixel(0, 0)), "{:?}", image.get_pixel(0, 0));
+    assert!(!is_clear(image.get_pixel(common::WIDTH / 2, common::HEIGHT / 2)));
+
+    common::assert_golden(&mut bundle, "texture_pipeline_msaa", common::TOLERANCE);
+}
+
+#[test]
+fn instanced_pipeline() {
+    let mut bundle = common::bundle();
+    let pipeline = bundle.texture_pipeline_instanced();
+
+    let mesh = quad(0.2);
+    let mesh_buffer = mesh.mesh_buffer(&bundle);
+    let texture = Texture::from_image(&bundle, &checker_image(), "checker").unwrap();
+    let texture_bind_group = texture.bind_group(&bundle);
+    let vp = Uniform::new(glam::Mat4::IDENTITY);
+    let vp_bind_group = vp.bind_group(&bundle);
+
+    let instances = Instances::new(vec![
+        Instance::new(glam::Mat4::from_translation(glam::vec3(-0.5, -0.5, 0.0))),
+        Instance::new(glam::Mat4::from_translation(glam::vec3(0.5, -0.5, 0.0))),
+        Instance::new(glam::Mat4::from_translation(glam::vec3(0.0, 0.5, 0.0))),
+    ]);
+    let instance_buffer = wgpu::util::DeviceExt::create_buffer_init(
+        bundle.device(),
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("Instance Buffer"),
+            contents: instances.to_bytes(),
+            usage: wgpu::BufferUsages::VERTEX,
+        },
+    );
+
+    submit_pass(&mut bundle, |frame, depth| {
+        let mut pass = frame.render_pass(CLEAR_COLOR, depth);
+        pass.render_texture_instanced(
+            mesh_buffer.vertex_buffer_slice(),
+            mesh_buffer.index_buffer_slice(),
+            0..mesh_buffer.index_count(),
+            &texture_bind_group,
+            &vp_bind_group,
+            instance_buffer.slice(..),
+            0..instances.len() as u32,
+            &pipeline,
+        );
+    });
+
+    common::assert_golden(&mut bundle, "instanced_pipeline", common::TOLERANCE);
+}
+
+#[test]
+fn text_pipeline() {
+    let mut bundle = common::bundle();
+    let pipeline = bundle.text_pipeline();
+
+    // A coverage mask shaped like a ring stands in for a rasterized glyph.
+    let dimensions = (16, 16);
+    let coverage = (0..dimensions.0 * dimensions.1)
+        .map(|i| {
+            let x = (i % dimensions.0) as f32 - 7.5;
+            let y = (i / dimensions.0) as f32 - 7.5;
+            let distance = (x * x + y * y).sqrt();
+            if (4.0..7.0).contains(&distance) {
+                255
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<u8>>();
+
+    let mesh = quad(0.6);
+    let mesh_buffer = mesh.mesh_buffer(&bundle);
+    let texture = Texture::from_r8u_bytes(&bundle, &coverage, dimensions, "glyph").unwrap();
+    let texture_bind_group = texture.bind_group(&bundle);
+    let mvp = Uniform::new(glam::Mat4::IDENTITY);
+    let mvp_bind_group = mvp.bind_group(&bundle);
+    let color = Uniform::new(glam::vec4(1.0, 0.8, 0.0, 1.0));
+    let color_bind_group = color.bind_group(&bundle);
+
+    submit_pass(&mut bundle, |frame, depth| {
+        let mut pass = frame.render_pass(CLEAR_COLOR, depth);
+        pass.render_text(
+            mesh_buffer.vertex_buffer_slice(),
+            mesh_buffer.index_buffer_slice(),
+            0..mesh_buffer.index_count(),
+            &texture_bind_group,
+            &mvp_bind_group,
+            &color_bind_group,
+            &pipeline,
+        );
+    });
+
+    common::assert_golden(&mut bundle, "text_pipeline", common::TOLERANCE);
+}
+
+#[test]
+fn compare_reports_pixels_over_tolerance() {
+    let reference = image::RgbaImage::from_pixel(4, 4, image::Rgba([100, 100, 100, 255]));
+    let mut actual = reference.clone();
+    actual.put_pixel(1, 1, image::Rgba([104, 100, 100, 255]));
+    actual.put_pixel(2, 2, image::Rgba([100, 140, 100, 255]));
+
+    let comparison = common::compare(&actual, &reference, 8);
+
+    assert_eq!(comparison.mismatched, 1);
+    assert_eq!(comparison.max_difference, 40);
+    assert_eq!(comparison.diff.get_pixel(2, 2), &image::Rgba([255, 0, 0, 255]));
+    assert_ne!(comparison.diff.get_pixel(1, 1), &image::Rgba([255, 0, 0, 255]));
+}
+
+#[test]
+fn resize_recreates_targets() {
+    let mut bundle = common::bundle();
+    bundle.resize(64, 32);
+
+    submit_pass(&mut bundle, |frame, depth| {
+        frame.render_pass(CLEAR_COLOR, depth);
+    });
+    let surface_texture = bundle.surface_texture().unwrap().unwrap();
+    let image = bundle.read_surface_texture(&surface_texture).unwrap();
+
+    assert_eq!(image.dimensions(), (64, 32));
+}
+
+#[test]
+fn unsupported_sample_count() {
+    let mut bundle = common::bundle();
+
+    assert!(matches!(bundle.set_sample_count(3), Err(heptagon::Error::UnsupportedSampleCount(3))));
+    assert_eq!(bundle.sample_count(), 1);
+    assert!(bundle.msaa_texture().is_none());
+}
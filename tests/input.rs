@@ -0,0 +1,163 @@
+use heptagon::main_loop::{ActionMap, Axis2dBinding, Binding, CursorMode, Input, Key, Modifiers, MouseButton, RecordedEvent};
+
+#[test]
+fn injected_keys_are_pressed_held_and_released() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.press_key(Key::W);
+    input.end_frame();
+    assert!(input.key_pressed(Key::W));
+    assert!(input.key_held(Key::W));
+
+    input.begin_frame();
+    input.end_frame();
+    assert!(!input.key_pressed(Key::W));
+    assert!(input.key_held(Key::W));
+
+    input.begin_frame();
+    input.release_key(Key::W);
+    input.end_frame();
+    assert!(input.key_released(Key::W));
+    assert!(!input.key_held(Key::W));
+}
+
+#[test]
+fn injected_mouse_state() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.press_mouse(MouseButton::Right);
+    input.move_mouse(3.0, -2.0);
+    input.set_cursor_position(40.0, 30.0);
+    input.end_frame();
+
+    assert!(input.mouse_pressed(MouseButton::Right));
+    assert_eq!(input.mouse_delta(), (3.0, -2.0));
+    assert_eq!(input.mouse_pos(), Some((40.0, 30.0)));
+
+    input.begin_frame();
+    input.end_frame();
+    assert_eq!(input.mouse_delta(), (0.0, 0.0));
+    assert!(input.mouse_held(MouseButton::Right));
+    assert!(!input.mouse_held(MouseButton::Left));
+    assert!(!input.mouse_held(MouseButton::Other(300)));
+}
+
+#[test]
+fn other_mouse_buttons_do_not_collide_with_named_ones() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.press_mouse(MouseButton::Other(0));
+    input.press_mouse(MouseButton::Other(2));
+    input.press_mouse(MouseButton::Other(300));
+    input.end_frame();
+    assert!(input.mouse_pressed(MouseButton::Other(0)));
+    assert!(input.mouse_held(MouseButton::Other(2)));
+    assert!(!input.mouse_held(MouseButton::Left));
+    assert!(!input.mouse_held(MouseButton::Middle));
+
+    input.begin_frame();
+    input.press_mouse(MouseButton::Left);
+    input.release_mouse(MouseButton::Other(0));
+    input.end_frame();
+    assert!(input.mouse_released(MouseButton::Other(0)));
+    assert!(!input.mouse_held(MouseButton::Other(0)));
+    assert!(input.mouse_pressed(MouseButton::Left));
+}
+
+#[test]
+fn scroll_delta_is_two_dimensional_and_in_lines() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.scroll(1.0, -2.0);
+    input.inject(&RecordedEvent::MouseWheel {
+        x: 0.0,
+        y: 76.0,
+        pixels: true,
+    });
+    input.end_frame();
+    assert_eq!(input.scroll_delta(), (1.0, 0.0));
+
+    input.begin_frame();
+    input.end_frame();
+    assert_eq!(input.scroll_delta(), (0.0, 0.0));
+}
+
+#[test]
+fn cursor_modes() {
+    let mut input = Input::new();
+    assert_eq!(input.cursor_mode(), CursorMode::Normal);
+
+    input.set_mouse_lock(true);
+    assert_eq!(input.cursor_mode(), CursorMode::Locked);
+    assert!(input.mouse_lock());
+
+    input.set_cursor_mode(CursorMode::Confined);
+    assert!(!input.mouse_lock());
+}
+
+#[test]
+fn action_map_reads_injected_input() {
+    let mut action_map = ActionMap::new();
+    action_map.bind("save", Binding::Chord(vec![Key::LControl, Key::S]));
+    action_map.bind_axis_2d("move", Axis2dBinding::arrows());
+
+    let mut input = Input::new();
+    input.begin_frame();
+    input.press_key(Key::LControl);
+    input.press_key(Key::S);
+    input.press_key(Key::Up);
+    input.press_key(Key::Right);
+    input.end_frame();
+
+    assert!(action_map.action_pressed(&input, "save"));
+    let movement = action_map.axis_2d(&input, "move");
+    assert!((movement.length() - 1.0).abs() < 1e-5);
+    assert!(movement.x > 0.0 && movement.y > 0.0);
+}
+
+#[test]
+fn typed_text_and_modifiers() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.set_modifiers(Modifiers::SHIFT);
+    input.type_text("Hé\u{8}!");
+    input.press_key(Key::Back);
+    input.press_key(Key::Back);
+    input.end_frame();
+
+    assert_eq!(input.text(), "Hé!");
+    assert!(input.shift());
+    assert!(!input.ctrl());
+    assert!(input.key_pressed(Key::Back));
+    assert!(input.key_pressed_repeat(Key::Back));
+
+    input.begin_frame();
+    input.end_frame();
+    assert_eq!(input.text(), "");
+    assert!(input.shift());
+}
+
+#[test]
+fn focused_and_hovered_window() {
+    let mut input = Input::new();
+    assert_eq!(input.focused_window(), None);
+
+    input.begin_frame();
+    input.inject(&RecordedEvent::Focused(true));
+    input.inject(&RecordedEvent::CursorEntered);
+    input.end_frame();
+    assert!(input.focused_window().is_some());
+    assert_eq!(input.hovered_window(), input.focused_window());
+
+    input.begin_frame();
+    input.inject(&RecordedEvent::Focused(false));
+    input.inject(&RecordedEvent::CursorLeft);
+    input.end_frame();
+    assert_eq!(input.focused_window(), None);
+    assert_eq!(input.hovered_window(), None);
+}
@@ -0,0 +1,99 @@
+use heptagon::rendering::*;
+
+const USAGE: wgpu::TextureUsages = wgpu::TextureUsages::TEXTURE_BINDING
+    .union(wgpu::TextureUsages::COPY_DST)
+    .union(wgpu::TextureUsages::COPY_SRC);
+
+fn checker_bytes(size: u32) -> Vec<u8> {
+    let image = image::RgbaImage::from_fn(size, size, |x, y| {
+        if (x + y) % 2 == 0 {
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            image::Rgba([0, 0, 255, 255])
+        }
+    });
+
+    image.into_raw()
+}
+
+fn assert_close(actual: &image::Rgba<u8>, expected: [u8; 4]) {
+    for (actual, expected) in actual.0.iter().zip(expected) {
+        assert!(actual.abs_diff(expected) <= 8, "{:?} is not close to {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn level_count_covers_the_larger_side() {
+    assert_eq!(Mipmaps::level_count((1, 1)), 1);
+    assert_eq!(Mipmaps::level_count((4, 4)), 3);
+    assert_eq!(Mipmaps::level_count((256, 64)), 9);
+    assert_eq!(Mipmaps::level_count((5, 3)), 3);
+    assert_eq!(Mipmaps::level_dimensions((256, 64), 8), (1, 1));
+}
+
+#[test]
+fn gpu_and_cpu_levels_average_the_base() {
+    let bundle = bundle::Bundle::headless(8, 8).unwrap();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let bytes = checker_bytes(8);
+
+    for mipmaps in [Mipmaps::Generate, Mipmaps::GenerateCpu] {
+        let texture = Texture::from_bytes_custom_with_mipmaps(
+            &bundle, "checker", format, &bytes, (8, 8), 4, USAGE, mipmaps,
+        )
+        .unwrap();
+
+        for level in 1..4 {
+            let dimensions = Mipmaps::level_dimensions((8, 8), level);
+            let image = bundle.read_texture_level(texture.texture(), format, dimensions, level).unwrap();
+            assert_eq!(image.dimensions(), dimensions);
+            for pixel in image.pixels() {
+                assert_close(pixel, [128, 0, 128, 255]);
+            }
+        }
+    }
+}
+
+#[test]
+fn cpu_fallback_needs_one_byte_per_component() {
+    let bundle = bundle::Bundle::headless(8, 8).unwrap();
+
+    let glyph = Texture::from_r8u_bytes_with_mipmaps(&bundle, &[255; 16], (4, 4), Mipmaps::GenerateCpu, "glyph");
+    assert!(glyph.is_ok());
+
+    let result = Texture::from_bytes_custom_with_mipmaps(
+        &bundle,
+        "half",
+        wgpu::TextureFormat::Rgba16Float,
+        &[0; 128],
+        (4, 4),
+        8,
+        USAGE,
+        Mipmaps::GenerateCpu,
+    );
+    assert!(matches!(result, Err(heptagon::Error::UnsupportedFormat(_))));
+}
+
+#[test]
+fn blit_pipelines_are_shared_per_format() {
+    let bundle = bundle::Bundle::headless(8, 8).unwrap();
+    let bytes = checker_bytes(8);
+
+    for label in ["first", "second"] {
+        Texture::from_bytes_custom_with_mipmaps(
+            &bundle,
+            label,
+            wgpu::TextureFormat::Rgba8Unorm,
+            &bytes,
+            (8, 8),
+            4,
+            USAGE,
+            Mipmaps::Generate,
+        )
+        .unwrap();
+    }
+    assert_eq!(bundle.blit_cache().len(), 1);
+
+    bundle.blit_cache().clear();
+    assert!(bundle.blit_cache().is_empty());
+}
@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+use heptagon::main_loop::{FramePacing, RedrawMode};
+
+#[test]
+fn frame_time_follows_target_fps() {
+    let mut frame_pacing = FramePacing::new();
+    assert_eq!(frame_pacing.frame_time(), None);
+
+    frame_pacing.set_target_fps(Some(50.0));
+    assert_eq!(frame_pacing.frame_time(), Some(Duration::from_millis(20)));
+
+    frame_pacing.set_target_fps(Some(0.0));
+    assert_eq!(frame_pacing.frame_time(), None);
+}
+
+#[test]
+fn redraw_mode_and_idle_default_to_continuous_frames() {
+    let mut frame_pacing = FramePacing::default();
+    assert_eq!(frame_pacing.redraw_mode(), RedrawMode::Continuous);
+    assert!(!frame_pacing.idle_when_unfocused());
+
+    frame_pacing.set_redraw_mode(RedrawMode::OnInput);
+    frame_pacing.set_idle_when_unfocused(true);
+    assert_eq!(frame_pacing.redraw_mode(), RedrawMode::OnInput);
+    assert!(frame_pacing.idle_when_unfocused());
+}
+
+#[test]
+fn target_fps_sleeps_until_the_frame_time_passed() {
+    let mut frame_pacing = FramePacing::new();
+    frame_pacing.set_target_fps(Some(50.0));
+    let last = Instant::now();
+
+    assert_eq!(frame_pacing.sleep_duration(last, last), Some(Duration::from_millis(20)));
+    assert_eq!(
+        frame_pacing.sleep_duration(last, last + Duration::from_millis(15)),
+        Some(Duration::from_millis(5))
+    );
+    // Late frames run right away without catching up.
+    assert_eq!(frame_pacing.sleep_duration(last, last + Duration::from_millis(20)), None);
+    assert_eq!(frame_pacing.sleep_duration(last, last + Duration::from_millis(35)), None);
+
+    // Waiting for input does not change the cap of frames which are run.
+    frame_pacing.set_redraw_mode(RedrawMode::OnInput);
+    assert_eq!(
+        frame_pacing.sleep_duration(last, last + Duration::from_millis(15)),
+        Some(Duration::from_millis(5))
+    );
+}
+
+#[test]
+fn unlimited_and_vsync_frames_do_not_sleep() {
+    // Vsync is a present mode of the MainLoop, presenting blocks instead of the pacing.
+    let mut frame_pacing = FramePacing::new();
+    let last = Instant::now();
+    assert_eq!(frame_pacing.sleep_duration(last, last), None);
+    assert_eq!(frame_pacing.sleep_duration(last, last + Duration::from_millis(1)), None);
+
+    frame_pacing.set_target_fps(Some(0.0));
+    assert_eq!(frame_pacing.sleep_duration(last, last), None);
+}
@@ -0,0 +1,76 @@
+use heptagon::main_loop::{Input, Key, MouseButton, RecordedEvent, Recording};
+
+fn events() -> Vec<RecordedEvent> {
+    vec![
+        RecordedEvent::Key {
+            scancode: 17,
+            key: Some(Key::W),
+            pressed: true,
+        },
+        RecordedEvent::MouseButton {
+            button: MouseButton::Left,
+            pressed: false,
+        },
+        RecordedEvent::CursorMoved { x: 10.5, y: 20.0 },
+        RecordedEvent::MouseWheel {
+            x: 0.0,
+            y: -1.0,
+            pixels: false,
+        },
+        RecordedEvent::MouseMotion { x: 3.0, y: -2.0 },
+        RecordedEvent::ReceivedCharacter('é'),
+        RecordedEvent::ModifiersChanged(winit::event::ModifiersState::CTRL),
+        RecordedEvent::Resized {
+            width: 640,
+            height: 480,
+        },
+        RecordedEvent::Focused(false),
+        RecordedEvent::CloseRequested,
+    ]
+}
+
+#[test]
+fn events_convert_to_winit_and_back() {
+    for event in events() {
+        assert_eq!(RecordedEvent::from_event(&event.to_event().unwrap()), Some(event));
+    }
+}
+
+#[test]
+fn recording_round_trips_through_json() {
+    let mut recording = Recording::new();
+    for event in events() {
+        recording.record_event(&event.to_event().unwrap());
+    }
+    recording.end_frame(0.016);
+    recording.end_frame(0.017);
+
+    let loaded = Recording::from_json(&recording.to_json()).unwrap();
+
+    assert_eq!(loaded.frames().len(), 2);
+    assert_eq!(loaded.frames()[0].events, events());
+    assert_eq!(loaded.frames()[1].delta, 0.017);
+    assert!(loaded.frames()[1].events.is_empty());
+}
+
+#[test]
+fn replay_runs_updates_without_a_window() {
+    let key = |pressed| RecordedEvent::Key {
+        scancode: 17,
+        key: Some(Key::W),
+        pressed,
+    };
+    let mut recording = Recording::new();
+    recording.record_event(&key(true).to_event().unwrap());
+    recording.end_frame(0.5);
+    recording.end_frame(0.25);
+    recording.record_event(&key(false).to_event().unwrap());
+    recording.end_frame(0.125);
+
+    let mut updates = Vec::new();
+    recording.replay(&mut Input::new(), |stats, input| {
+        updates.push((stats.delta(), input.key_pressed(Key::W), input.key_held(Key::W)));
+    });
+
+    assert_eq!(updates, [(0.5, true, true), (0.25, false, true), (0.125, false, false)]);
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use heptagon::rendering::*;
+
+#[test]
+fn identical_options_share_a_sampler() {
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let cache = bundle.sampler_cache();
+    cache.clear();
+
+    let tiled = SamplerOptions::nearest().with_address_mode(wgpu::AddressMode::Repeat);
+    let a = bundle.sampler(&tiled);
+    let b = bundle.sampler(&SamplerOptions::nearest().with_address_mode(wgpu::AddressMode::Repeat));
+    assert!(Arc::ptr_eq(&a, &b));
+    assert_eq!(cache.len(), 1);
+
+    let c = bundle.sampler(&tiled.with_lod_clamp(0.0, 4.0));
+    assert!(!Arc::ptr_eq(&a, &c));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn textures_use_the_cache() {
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    bundle.sampler_cache().clear();
+    let image = image::DynamicImage::new_rgba8(4, 4);
+    let options = TextureOptions {
+        sampler: Some(SamplerOptions::trilinear().with_anisotropy(4)),
+        ..Default::default()
+    };
+
+    let mut a = Texture::from_image_with_options(&bundle, &image, &options, "a").unwrap();
+    let mut b = Texture::from_image_with_options(&bundle, &image, &options, "b").unwrap();
+    assert!(Arc::ptr_eq(a.sampler_mut(), b.sampler_mut()));
+    assert_eq!(bundle.sampler_cache().len(), 1);
+
+    Texture::from_image(&bundle, &image, "default").unwrap();
+    assert_eq!(bundle.sampler_cache().len(), 2);
+}
+
+#[test]
+fn anisotropy_of_one_is_off() {
+    assert_eq!(SamplerOptions::trilinear().with_anisotropy(1).anisotropy_clamp, None);
+    assert_eq!(SamplerOptions::trilinear().with_anisotropy(16).anisotropy_clamp.map(|clamp| clamp.get()), Some(16));
+}
+
+#[test]
+fn anisotropy_is_clamped_and_filters_linearly() {
+    let clamp = |anisotropy| SamplerOptions::nearest().with_anisotropy(anisotropy).anisotropy_clamp.map(|clamp| clamp.get());
+    assert_eq!(clamp(0), None);
+    assert_eq!(clamp(3), Some(2));
+    assert_eq!(clamp(12), Some(8));
+    assert_eq!(clamp(200), Some(16));
+
+    let options = SamplerOptions::nearest().with_anisotropy(4);
+    assert_eq!(options, SamplerOptions::trilinear().with_anisotropy(4));
+    assert_eq!(SamplerOptions::nearest().with_anisotropy(1).min_filter, wgpu::FilterMode::Nearest);
+}
+
+#[test]
+fn invalid_anisotropy_is_normalized() {
+    let descriptor = |clamp| {
+        SamplerOptions {
+            anisotropy_clamp: std::num::NonZeroU8::new(clamp),
+            ..SamplerOptions::trilinear()
+        }
+        .descriptor(None)
+        .anisotropy_clamp
+        .map(|clamp| clamp.get())
+    };
+    assert_eq!(descriptor(1), None);
+    assert_eq!(descriptor(3), Some(2));
+    assert_eq!(descriptor(255), Some(16));
+}
+
+#[test]
+fn anisotropy_without_linear_filters_is_normalized() {
+    let options = SamplerOptions {
+        anisotropy_clamp: std::num::NonZeroU8::new(4),
+        ..SamplerOptions::nearest()
+    };
+    let descriptor = options.descriptor(None);
+    assert_eq!(descriptor.mag_filter, wgpu::FilterMode::Linear);
+    assert_eq!(descriptor.min_filter, wgpu::FilterMode::Linear);
+    assert_eq!(descriptor.mipmap_filter, wgpu::FilterMode::Linear);
+
+    // The cache creates the sampler instead of panicking, and shares it with the normalized options.
+    let bundle = bundle::Bundle::headless(4, 4).unwrap();
+    let a = bundle.sampler(&options);
+    let b = bundle.sampler(&SamplerOptions::trilinear().with_anisotropy(4));
+    assert!(Arc::ptr_eq(&a, &b));
+}
@@ -0,0 +1,125 @@
+use heptagon::main_loop::{Gesture, GestureRecognizer, Input, RecordedEvent, TouchEvent, TouchPhase};
+
+fn process(recognizer: &mut GestureRecognizer, id: u64, phase: TouchPhase, x: f32, y: f32) -> Vec<Gesture> {
+    let mut gestures = Vec::new();
+    recognizer.process(&TouchEvent::new(id, phase, x, y), &mut gestures);
+    gestures
+}
+
+#[test]
+fn touches_are_tracked_per_finger() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.touch(0, TouchPhase::Started, 10.0, 10.0);
+    input.touch(1, TouchPhase::Started, 50.0, 50.0);
+    input.touch(0, TouchPhase::Moved, 15.0, 12.0);
+    input.end_frame();
+    assert_eq!(input.touches().active_count(), 2);
+    let touch = input.touches().get(0).unwrap();
+    assert_eq!(touch.position, glam::vec2(15.0, 12.0));
+    assert_eq!(touch.start_position, glam::vec2(10.0, 10.0));
+    assert_eq!(touch.delta, glam::vec2(5.0, 2.0));
+
+    input.begin_frame();
+    input.touch(1, TouchPhase::Cancelled, 50.0, 50.0);
+    input.end_frame();
+    assert_eq!(input.touches().get(1).unwrap().phase, TouchPhase::Cancelled);
+    assert_eq!(input.touches().active_count(), 1);
+    assert_eq!(input.touches().get(0).unwrap().delta, glam::Vec2::ZERO);
+
+    input.begin_frame();
+    input.end_frame();
+    assert!(input.touches().get(1).is_none());
+}
+
+#[test]
+fn tap_and_double_tap() {
+    let mut recognizer = GestureRecognizer::new();
+
+    assert!(process(&mut recognizer, 0, TouchPhase::Started, 100.0, 100.0).is_empty());
+    recognizer.advance(0.1);
+    let position = glam::vec2(102.0, 100.0);
+    assert_eq!(
+        process(&mut recognizer, 0, TouchPhase::Ended, 102.0, 100.0),
+        vec![Gesture::Tap { position }]
+    );
+
+    recognizer.advance(0.1);
+    process(&mut recognizer, 1, TouchPhase::Started, 105.0, 100.0);
+    assert_eq!(
+        process(&mut recognizer, 1, TouchPhase::Ended, 105.0, 100.0),
+        vec![Gesture::DoubleTap {
+            position: glam::vec2(105.0, 100.0)
+        }]
+    );
+}
+
+#[test]
+fn long_or_moving_touch_is_not_a_tap() {
+    let mut recognizer = GestureRecognizer::new();
+
+    process(&mut recognizer, 0, TouchPhase::Started, 0.0, 0.0);
+    recognizer.advance(1.0);
+    assert!(process(&mut recognizer, 0, TouchPhase::Ended, 0.0, 0.0).is_empty());
+
+    process(&mut recognizer, 1, TouchPhase::Started, 0.0, 0.0);
+    process(&mut recognizer, 1, TouchPhase::Moved, 50.0, 0.0);
+    assert!(process(&mut recognizer, 1, TouchPhase::Ended, 0.0, 0.0).is_empty());
+}
+
+#[test]
+fn pinch_and_two_finger_pan() {
+    let mut recognizer = GestureRecognizer::new();
+    process(&mut recognizer, 0, TouchPhase::Started, 0.0, 0.0);
+    process(&mut recognizer, 1, TouchPhase::Started, 100.0, 0.0);
+
+    assert_eq!(
+        process(&mut recognizer, 1, TouchPhase::Moved, 200.0, 0.0),
+        vec![
+            Gesture::Pinch {
+                center: glam::vec2(100.0, 0.0),
+                scale: 2.0
+            },
+            Gesture::Pan {
+                delta: glam::vec2(50.0, 0.0)
+            },
+        ]
+    );
+
+    process(&mut recognizer, 0, TouchPhase::Moved, 0.0, 20.0);
+    assert_eq!(
+        process(&mut recognizer, 1, TouchPhase::Moved, 200.0, 20.0)[1],
+        Gesture::Pan {
+            delta: glam::vec2(0.0, 10.0)
+        }
+    );
+
+    // Neither finger of a two finger gesture is a tap.
+    assert!(process(&mut recognizer, 0, TouchPhase::Ended, 0.0, 20.0).is_empty());
+    assert!(process(&mut recognizer, 1, TouchPhase::Ended, 200.0, 20.0).is_empty());
+}
+
+#[test]
+fn input_recognizes_gestures_of_injected_touches() {
+    let mut input = Input::new();
+
+    input.begin_frame();
+    input.touch(7, TouchPhase::Started, 30.0, 40.0);
+    input.end_frame();
+    assert!(input.gestures().is_empty());
+
+    input.advance_time(0.05);
+    input.begin_frame();
+    input.touch(7, TouchPhase::Ended, 30.0, 40.0);
+    input.end_frame();
+    assert_eq!(
+        input.gestures(),
+        &[Gesture::Tap {
+            position: glam::vec2(30.0, 40.0)
+        }]
+    );
+
+    let event = RecordedEvent::Touch(TouchEvent::new(7, TouchPhase::Moved, 1.0, 2.0));
+    assert_eq!(RecordedEvent::from_event(&event.to_event().unwrap()), Some(event));
+}